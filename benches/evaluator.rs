@@ -0,0 +1,53 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use rand::{seq::SliceRandom, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use poker_simulator::table::{
+    components::Card,
+    hand_evaluation::{self as he, Rankable},
+    lookup_evaluation::LookupEvaluator,
+};
+
+const N_HANDS: usize = 1000;
+
+/// Random 7-card hands, the same on every run
+fn seven_card_hands() -> Vec<Vec<Card>> {
+    let mut rng = ChaCha8Rng::seed_from_u64(0);
+    let deck: Vec<u8> = (0..52).collect();
+    (0..N_HANDS)
+        .map(|_| {
+            deck.choose_multiple(&mut rng, 7)
+                .map(|&card| Card(card))
+                .collect()
+        })
+        .collect()
+}
+
+fn rank_seven_cards(c: &mut Criterion) {
+    let hands = seven_card_hands();
+    let bitset_hands: Vec<Vec<he::Card>> = hands
+        .iter()
+        .map(|hand| hand.iter().map(|card| he::Card::new(card.0)).collect())
+        .collect();
+    let evaluator = LookupEvaluator::holdem();
+
+    let mut group = c.benchmark_group("rank 1000 seven-card hands");
+    group.bench_function("bitsets", |b| {
+        b.iter(|| {
+            for hand in bitset_hands.iter() {
+                black_box(hand.rank());
+            }
+        })
+    });
+    group.bench_function("lookup table", |b| {
+        b.iter(|| {
+            for hand in hands.iter() {
+                black_box(evaluator.rank(hand));
+            }
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, rank_seven_cards);
+criterion_main!(benches);
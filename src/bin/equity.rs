@@ -0,0 +1,108 @@
+//! Win, tie and equity of known hands or ranges by the river.
+//!
+//! `equity AhKd QsQc [--board 7h2sKd] [--dead 3c] [--omaha | --short-deck] [--samples N]
+//! [--exact-limit N] [--seed N]`, or with ranges: `equity AhKd "TT+, AKs, KQo:0.5"`
+
+use poker_simulator::table::{
+    components::parse_cards,
+    equity::{calculate_equity, range_equity, EquityConfig},
+    range::Range,
+    resources::GameVariant,
+};
+
+const OPTIONS_WITH_VALUE: [&str; 5] = ["--board", "--dead", "--samples", "--exact-limit", "--seed"];
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let value_of = |flag: &str| -> Option<&String> {
+        args.iter().position(|arg| arg == flag).map(|index| {
+            args.get(index + 1)
+                .unwrap_or_else(|| panic!("{} takes a value", flag))
+        })
+    };
+    let number_of = |flag: &str| -> Option<u64> {
+        value_of(flag).map(|value| {
+            value
+                .parse()
+                .unwrap_or_else(|_| panic!("{} takes an unsigned integer", flag))
+        })
+    };
+    let cards_of = |text: &str| {
+        parse_cards(text).unwrap_or_else(|error| {
+            eprintln!("{}", error);
+            std::process::exit(1);
+        })
+    };
+
+    let mut config = EquityConfig::default();
+    if args.iter().any(|arg| arg == "--omaha") {
+        config.variant = GameVariant::Omaha;
+    } else if args.iter().any(|arg| arg == "--short-deck") {
+        config.variant = GameVariant::ShortDeck;
+    }
+    if let Some(n_samples) = number_of("--samples") {
+        config.n_samples = n_samples;
+    }
+    if let Some(max_exact_boards) = number_of("--exact-limit") {
+        config.max_exact_boards = max_exact_boards;
+    }
+    config.seed = number_of("--seed");
+    let board = value_of("--board")
+        .map(|text| cards_of(text))
+        .unwrap_or_default();
+    let dead_cards = value_of("--dead")
+        .map(|text| cards_of(text))
+        .unwrap_or_default();
+
+    // Hands are the arguments that are neither options nor option values
+    let hands: Vec<&String> = args
+        .iter()
+        .enumerate()
+        .filter(|(index, arg)| {
+            let is_option_value =
+                *index > 0 && OPTIONS_WITH_VALUE.contains(&args[index - 1].as_str());
+            !arg.starts_with("--") && !is_option_value
+        })
+        .map(|(_, arg)| arg)
+        .collect();
+    let hole_cards: Result<Vec<_>, _> = hands.iter().map(|hand| parse_cards(hand)).collect();
+    let result = match hole_cards {
+        Ok(hole_cards) => calculate_equity(&config, &hole_cards, &board, &dead_cards),
+        // Not all known hands: ranges
+        Err(_) => {
+            let ranges: Vec<Range> = hands
+                .iter()
+                .map(|hand| {
+                    hand.parse().unwrap_or_else(|error| {
+                        eprintln!("{}", error);
+                        std::process::exit(1);
+                    })
+                })
+                .collect();
+            range_equity(&config, &ranges, &board, &dead_cards)
+        }
+    };
+    let result = match result {
+        Ok(result) => result,
+        Err(error) => {
+            eprintln!("{}", error);
+            std::process::exit(1);
+        }
+    };
+    println!(
+        "{} {} boards",
+        if result.is_exact {
+            "Enumerated"
+        } else {
+            "Sampled"
+        },
+        result.n_boards
+    );
+    println!("{:<12} {:>8} {:>8} {:>8}", "Hand", "Win", "Tie", "Equity");
+    for (hand, equity) in hands.iter().zip(result.players.iter()) {
+        println!(
+            "{:<12} {:>7.2}% {:>7.2}% {:>7.2}%",
+            hand, equity.win, equity.tie, equity.equity
+        );
+    }
+}
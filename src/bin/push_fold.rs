@@ -0,0 +1,92 @@
+//! Nash push/fold ranges by position and effective stack.
+//!
+//! `push_fold [--seats N] [--stacks 5,10,15] [--iterations N] [--deals N] [--seed N]`
+//!
+//! Stacks are in big blinds. Equities of the starting hands come from `--deals` sampled deals
+//! for every two kinds of hands.
+
+use poker_simulator::table::{
+    cfr::PreflopEquities,
+    push_fold::{NashCharts, DEFAULT_CHART_STACKS_BB},
+    range::Range,
+    resources::TableConfig,
+};
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    let value_of = |flag: &str| -> Option<&String> {
+        args.iter().position(|arg| arg == flag).map(|index| {
+            args.get(index + 1)
+                .unwrap_or_else(|| panic!("{} takes a value", flag))
+        })
+    };
+    let number_of = |flag: &str| -> Option<u64> {
+        value_of(flag).map(|value| {
+            value
+                .parse()
+                .unwrap_or_else(|_| panic!("{} takes an unsigned integer", flag))
+        })
+    };
+    let mut config = TableConfig::default();
+    if let Some(n_seats) = number_of("--seats") {
+        config.n_seats = n_seats as u8;
+    }
+    let stacks_bb: Vec<f64> = match value_of("--stacks") {
+        Some(text) => text
+            .split(',')
+            .map(|stack| {
+                stack
+                    .trim()
+                    .parse()
+                    .unwrap_or_else(|_| panic!("Invalid stack {}", stack))
+            })
+            .collect(),
+        None => DEFAULT_CHART_STACKS_BB.to_vec(),
+    };
+    let n_iterations = number_of("--iterations").unwrap_or(300) as u32;
+    let n_deals = number_of("--deals").unwrap_or(500) as u32;
+    let seed = number_of("--seed").unwrap_or(0);
+
+    let equities = PreflopEquities::estimate(n_deals, seed);
+    let charts = NashCharts::generate(&equities, &config, &stacks_bb, n_iterations);
+    let n_seats = config.n_seats;
+    for chart in charts.charts.iter() {
+        println!("{}bb, {} seats", chart.stack_bb, n_seats);
+        for (position, range) in chart.push.iter().enumerate().skip(1).rev() {
+            print_range(
+                &format!("{} pushes", position_name(position, n_seats)),
+                range,
+            );
+        }
+        for (caller, by_pusher) in chart.call.iter().enumerate() {
+            for (pusher, range) in by_pusher.iter().enumerate().skip(caller + 1).rev() {
+                let situation = format!(
+                    "{} calls {}",
+                    position_name(caller, n_seats),
+                    position_name(pusher, n_seats)
+                );
+                print_range(&situation, range);
+            }
+        }
+        println!();
+    }
+}
+
+fn print_range(situation: &str, range: &Range) {
+    // 1326 combos of two cards
+    let share = range.n_combos() as f64 / 1326.0 * 100.0;
+    println!("  {:<16} {:>5.1}%  {}", situation, share, range);
+}
+
+fn position_name(position: usize, n_seats: u8) -> String {
+    let under_the_gun = n_seats as usize - 1;
+    match position {
+        0 => "BB".to_string(),
+        1 => "SB".to_string(),
+        2 => "BTN".to_string(),
+        _ if position == under_the_gun => "UTG".to_string(),
+        3 => "CO".to_string(),
+        4 => "HJ".to_string(),
+        _ => format!("UTG+{}", under_the_gun - position),
+    }
+}
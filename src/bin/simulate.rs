@@ -0,0 +1,122 @@
+//! Bot-vs-bot cash game without a window, as fast as the CPU allows.
+//!
+//! `simulate [--hands N] [--seats N] [--seed N] [--omaha | --short-deck] [--limit]
+//! [--equity-bots N] [--profiles] [--push-fold BB]`
+//!
+//! The first `--equity-bots` seats play by pot odds, the others at random or, with
+//! `--profiles`, as a mix of rule-based profiles. With `--push-fold`, every seat pushes or
+//! folds by Nash charts at an effective stack of `BB` big blinds or less.
+
+use std::{sync::Arc, time::Instant};
+
+use poker_simulator::table::{
+    bot::{EquityStrategy, RandomStrategy},
+    cfr::PreflopEquities,
+    profile::{Profile, ProfileStrategy},
+    push_fold::{NashCharts, ShortStackStrategy, DEFAULT_CHART_STACKS_BB},
+    resources::{BettingStructure, SeatStrategies, TableConfig},
+    simulation::Simulation,
+    strategy::Strategy,
+};
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    let value_of = |flag: &str| -> Option<u64> {
+        args.iter().position(|arg| arg == flag).map(|index| {
+            args.get(index + 1)
+                .and_then(|value| value.parse().ok())
+                .unwrap_or_else(|| panic!("{} takes an unsigned integer", flag))
+        })
+    };
+    let mut config = if args.iter().any(|arg| arg == "--omaha") {
+        TableConfig::pot_limit_omaha()
+    } else if args.iter().any(|arg| arg == "--short-deck") {
+        TableConfig::short_deck()
+    } else {
+        TableConfig::default()
+    };
+    if args.iter().any(|arg| arg == "--limit") {
+        config.betting_structure = BettingStructure::Limit;
+    }
+    if let Some(n_seats) = value_of("--seats") {
+        config.n_seats = n_seats as u8;
+    }
+    config.seed = value_of("--seed");
+    let n_hands = value_of("--hands").unwrap_or(100_000) as u32;
+    let n_equity_bots = value_of("--equity-bots").unwrap_or(0) as usize;
+    let mut profiles = args
+        .iter()
+        .any(|arg| arg == "--profiles")
+        .then(|| Profile::all().into_iter().cycle());
+
+    let mut strategies = SeatStrategies::default();
+    let names: Vec<String> = (0..config.n_seats as usize)
+        .map(|seat_index| {
+            if seat_index < n_equity_bots {
+                strategies
+                    .by_seat
+                    .insert(seat_index, Box::new(EquityStrategy::default()));
+                format!("Equity {}", seat_index)
+            } else if let Some(profile) = profiles.as_mut().and_then(Iterator::next) {
+                let name = format!("{} {}", profile.name, seat_index);
+                strategies
+                    .by_seat
+                    .insert(seat_index, Box::new(ProfileStrategy { profile }));
+                name
+            } else {
+                format!("Random {}", seat_index)
+            }
+        })
+        .collect();
+    if let Some(threshold_bb) = value_of("--push-fold") {
+        let stacks_bb: Vec<f64> = DEFAULT_CHART_STACKS_BB
+            .into_iter()
+            .filter(|stack_bb| *stack_bb <= threshold_bb as f64)
+            .collect();
+        let equities = PreflopEquities::estimate(200, config.seed.unwrap_or(0));
+        let charts = Arc::new(NashCharts::generate(&equities, &config, &stacks_bb, 300));
+        for seat_index in 0..config.n_seats as usize {
+            let deep = strategies
+                .by_seat
+                .remove(&seat_index)
+                .unwrap_or_else(|| Box::new(RandomStrategy) as Box<dyn Strategy>);
+            let short_stack = ShortStackStrategy {
+                charts: charts.clone(),
+                threshold_bb: threshold_bb as f64,
+                deep,
+            };
+            strategies.by_seat.insert(seat_index, Box::new(short_stack));
+        }
+    }
+    let big_blind = config.big_blind;
+    let started_at = Instant::now();
+    let mut simulation = Simulation::new(config, &names);
+    let first_hand_seed = simulation.state.hand_seed;
+    simulation.run(n_hands, |state, rng| strategies.decide(state, rng));
+    let elapsed = started_at.elapsed();
+
+    println!(
+        "{} hands in {:.2}s ({:.0} hands/s), first hand seed {}",
+        n_hands,
+        elapsed.as_secs_f64(),
+        n_hands as f64 / elapsed.as_secs_f64(),
+        first_hand_seed
+    );
+    println!(
+        "{:<4} {:<18} {:>12} {:>10} {:>10} {:>7} {:>7} {:>14}",
+        "Seat", "Name", "Net", "bb/100", "95% ±", "WTSD", "W$SD", "Showdown net"
+    );
+    for (seat_index, stats) in simulation.stats.iter().enumerate() {
+        println!(
+            "{:<4} {:<18} {:>12} {:>10.2} {:>10.2} {:>6.1}% {:>6.1}% {:>14}",
+            seat_index,
+            stats.name,
+            stats.net,
+            stats.bb_per_100(big_blind),
+            stats.bb_per_100_margin(big_blind),
+            stats.went_to_showdown() * 100.0,
+            stats.won_at_showdown() * 100.0,
+            stats.showdown_net
+        );
+    }
+}
@@ -3,6 +3,7 @@ use bevy::prelude::*;
 use crate::table::{
     components::{Amount, BoardCards, Card, PooledPot, Pot},
     events::TableUpdated,
+    resources::TableConfig,
 };
 
 use super::{
@@ -68,6 +69,7 @@ pub fn update_board(
         Query<&mut Text, With<PotText>>,
     )>,
     q_board: Query<BoardAttributes>,
+    config: Res<TableConfig>,
 ) {
     let table_updated: bool = table_updated_event_reader.read().last().is_some();
     if !table_updated {
@@ -76,7 +78,7 @@ pub fn update_board(
     let (pot, _, board_cards) = q_board.single();
     let mut q_pot_text = queries.p2();
     let mut pot_text = q_pot_text.single_mut();
-    pot_text.sections[0].value = format!("Pot: {}", pot.amount_str());
+    pot_text.sections[0].value = format!("Pot: {}", pot.amount_str(config.big_blind));
 
     let mut q_card_visibility = queries.p0();
     for (mut visibility, card_index) in q_card_visibility.iter_mut() {
@@ -1,6 +1,6 @@
 use bevy::prelude::*;
 
-use crate::table::components::Chips;
+use crate::table::actions::Action;
 
 #[derive(Component, Debug)]
 pub struct MainCamera;
@@ -23,6 +23,9 @@ pub struct StackText;
 #[derive(Component, Debug)]
 pub struct MovedChipsText;
 
+#[derive(Component, Debug)]
+pub struct ShownHandText;
+
 #[derive(Component, Debug, Clone)]
 pub struct BoardCardIndex(pub usize);
 
@@ -59,16 +62,22 @@ pub struct MoveControls;
 #[derive(Component, Debug)]
 pub struct ButtonRect(pub Rect);
 
+/// Buttons carry the action they send; raise is [[None]] when raising is not allowed. Raise
+/// size buttons step the raise down or up.
 #[derive(Component, Debug)]
 pub enum MoveButtons {
     FoldButton,
-    CallButton(Chips),
-    RaiseButton(Chips),
+    CallButton(Action),
+    RaiseButton(Option<Action>),
+    RaiseSizeButton(i32),
 }
 
 #[derive(Component, Debug)]
 pub struct FoldButton;
 
+#[derive(Component, Debug)]
+pub struct RaiseButton;
+
 #[derive(Component, Debug)]
 pub struct RaiseButtonText;
 
@@ -83,3 +92,6 @@ pub struct PauseButtonText;
 
 #[derive(Component, Debug)]
 pub struct DummyLabel;
+
+#[derive(Component, Debug, Clone)]
+pub struct TournamentResults;
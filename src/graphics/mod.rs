@@ -9,5 +9,6 @@ mod player;
 mod player_attributes;
 mod plugin;
 mod table_ui;
+mod tournament_results;
 
 pub use plugin::TableUiPlugin;
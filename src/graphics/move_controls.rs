@@ -1,20 +1,21 @@
 use bevy::{prelude::*, window::PrimaryWindow};
 
 use crate::table::{
-    components::{Amount, Chips, MovedChips, Player, PlayerInAction, PlayerIsHero, Pot, Street},
-    events::{HeroMoved, Move, TableUpdated},
+    actions::Action,
+    betting::Betting,
+    components::{Amount, Chips, MovedChips, Player, PlayerInAction, PlayerIsHero},
+    events::{HeroMoved, TableUpdated},
+    resources::GameStateResource,
 };
 
 use super::{
     base_components::{spawn_button, Container},
     components::{
-        ButtonRect, CallButtonText, DummyLabel, FoldButton, MoveButtons, MoveControls,
+        ButtonRect, CallButtonText, DummyLabel, FoldButton, MoveButtons, MoveControls, RaiseButton,
         RaiseButtonText,
     },
 };
 
-use crate::table::betting;
-
 pub fn spawn_move_controls(
     commands: &mut Commands,
     meshes: &mut ResMut<Assets<Mesh>>,
@@ -49,7 +50,7 @@ pub fn spawn_move_controls(
         button_size.clone(),
         "Call",
         "#047804",
-        MoveButtons::CallButton(Chips(0)),
+        MoveButtons::CallButton(Action::Check),
         CallButtonText,
     );
     let raise_button = spawn_button(
@@ -62,17 +63,58 @@ pub fn spawn_move_controls(
         button_size.clone(),
         "Raise",
         "#b81600",
-        MoveButtons::RaiseButton(Chips(0)),
+        (RaiseButton, MoveButtons::RaiseButton(None)),
         RaiseButtonText,
     );
+    let step_size = Vec2::new(30.0, 40.0);
+    let step_buttons: Vec<Entity> = [(-1, "-", 225.0), (1, "+", 260.0)]
+        .into_iter()
+        .map(|(step, text, x)| {
+            spawn_button(
+                commands,
+                color_materials,
+                meshes,
+                asset_server,
+                abs_parent_pos,
+                Vec3::new(x, 0.0, 0.0),
+                step_size,
+                text,
+                "#b81600",
+                (RaiseButton, MoveButtons::RaiseSizeButton(step)),
+                DummyLabel,
+            )
+        })
+        .collect();
     commands
         .entity(root)
-        .push_children(&[fold_button, call_button, raise_button]);
+        .push_children(&[fold_button, call_button, raise_button])
+        .push_children(&step_buttons);
     root
 }
 
+/// Steps the hero took from a pot-sized raise through [[raise_sizes]], until the hero moves
+#[derive(Resource, Debug, Default)]
+pub struct RaiseSize {
+    pub steps: i32,
+}
+
+/// Bet or raise sizes the hero can pick, in increasing order: the minimum, fractions of the
+/// pot and all-in, within `min_to..=max_to`
+fn raise_sizes(betting: &Betting, min_to: Chips, max_to: Chips) -> Vec<u32> {
+    let mut sizes: Vec<u32> = [0.5, 0.75, 1.0, 1.5, 2.0]
+        .into_iter()
+        .map(|fraction| betting.pot_raise_to(fraction))
+        .chain([min_to.0, max_to.0])
+        .map(|raise_to| raise_to.clamp(min_to.0, max_to.0))
+        .collect();
+    sizes.sort_unstable();
+    sizes.dedup();
+    sizes
+}
+
 pub fn handle_clicks_on_move_buttons(
     mut hero_moved_event_writer: EventWriter<HeroMoved>,
+    mut raise_size: ResMut<RaiseSize>,
     buttons: Res<ButtonInput<MouseButton>>,
     q_buttons: Query<(&ButtonRect, &MoveButtons)>,
     q_windows: Query<&Window, With<PrimaryWindow>>,
@@ -90,13 +132,20 @@ pub fn handle_clicks_on_move_buttons(
                 if button_rect.0.contains(point) {
                     match move_button {
                         MoveButtons::FoldButton => {
-                            hero_moved_event_writer.send(HeroMoved(Move(-1)));
+                            hero_moved_event_writer.send(HeroMoved(Action::Fold));
+                            raise_size.steps = 0;
+                        }
+                        MoveButtons::CallButton(action) => {
+                            hero_moved_event_writer.send(HeroMoved(*action));
+                            raise_size.steps = 0;
                         }
-                        MoveButtons::CallButton(chips) => {
-                            hero_moved_event_writer.send(HeroMoved(Move(chips.0 as i32)));
+                        MoveButtons::RaiseButton(Some(action)) => {
+                            hero_moved_event_writer.send(HeroMoved(*action));
+                            raise_size.steps = 0;
                         }
-                        MoveButtons::RaiseButton(chips) => {
-                            hero_moved_event_writer.send(HeroMoved(Move(chips.0 as i32)));
+                        MoveButtons::RaiseButton(None) => {}
+                        MoveButtons::RaiseSizeButton(step) => {
+                            raise_size.steps += step;
                         }
                     }
                 }
@@ -110,6 +159,7 @@ pub fn update_move_controls(
     mut q_visibilities: ParamSet<(
         Query<&mut Visibility, With<MoveControls>>,
         Query<&mut Visibility, With<FoldButton>>,
+        Query<&mut Visibility, With<RaiseButton>>,
     )>,
     mut q_texts: ParamSet<(
         Query<&mut Text, With<RaiseButtonText>>,
@@ -117,60 +167,90 @@ pub fn update_move_controls(
     )>,
     mut q_move_buttons: Query<&mut MoveButtons>,
     q_hero_in_action: Query<Entity, (With<Player>, With<PlayerIsHero>, With<PlayerInAction>)>,
-    q_players: Query<betting::PlayerAttributes, With<Player>>,
-    q_pot: Query<&Pot>,
-    q_street: Query<&Street>,
+    mut raise_size: ResMut<RaiseSize>,
+    game: Res<GameStateResource>,
 ) {
     let table_updated: bool = table_updated_event_reader.read().last().is_some();
-    if !table_updated {
+    if !table_updated && !raise_size.is_changed() {
         return;
     }
+    let config = &game.state.config;
 
     let mut q_move_controls_visibility = q_visibilities.p0();
     let mut move_controls_visibility = q_move_controls_visibility.single_mut();
     let hero_in_action: bool = q_hero_in_action.iter().last().is_some();
     if hero_in_action {
         *move_controls_visibility = Visibility::Visible;
-        let players = q_players.iter().collect::<Vec<_>>();
-        let betting = betting::Betting::new(&players, q_pot.single().amount(), q_street.single().0);
-        let move_options = betting.move_options();
-        let call_option = move_options[move_options.len() - 2];
-        let raise_option = move_options[move_options.len() - 1];
-        let tocall = betting.tocall();
-        if tocall == 0 {
-            let mut q_fold_button_visibility = q_visibilities.p1();
-            let mut fold_button_visibility = q_fold_button_visibility.single_mut();
-            *fold_button_visibility = Visibility::Hidden;
+        let Some(legal_actions) = game.state.legal_actions() else {
+            return;
+        };
+        let betting = game.state.betting();
+        let options = legal_actions.options();
+        // Check, call or calling all-in
+        let call_option = options[if legal_actions.can_check() { 0 } else { 1 }];
+        // Pot-sized raise within the bounds, stepped up or down by the hero. The bounds leave
+        // a single size in fixed-limit.
+        let raise_option = legal_actions.raise.map(|raise| {
+            let sizes = raise_sizes(&betting, raise.min_to, raise.max_to);
+            let pot_size = betting
+                .pot_raise_to(1.0)
+                .clamp(raise.min_to.0, raise.max_to.0);
+            let pot_index = sizes.iter().position(|size| *size == pot_size).unwrap_or(0) as i32;
+            let index = (pot_index + raise_size.steps).clamp(0, sizes.len() as i32 - 1);
+            // Steps past either end are dropped
+            if raise_size.steps != index - pot_index {
+                raise_size.steps = index - pot_index;
+            }
+            legal_actions.raise_action(Chips(sizes[index as usize]))
+        });
 
-            let mut q_call_button_text = q_texts.p1();
-            let mut call_button_text = q_call_button_text.single_mut();
-            call_button_text.sections[0].value = "Check".to_string();
+        let mut q_fold_button_visibility = q_visibilities.p1();
+        let mut fold_button_visibility = q_fold_button_visibility.single_mut();
+        *fold_button_visibility = if betting.tocall() > 0 {
+            Visibility::Visible
         } else {
-            let mut q_fold_button_visibility = q_visibilities.p1();
-            let mut fold_button_visibility = q_fold_button_visibility.single_mut();
-            *fold_button_visibility = Visibility::Visible;
+            Visibility::Hidden
+        };
 
-            let mut q_call_button_text = q_texts.p1();
-            let mut call_button_text = q_call_button_text.single_mut();
-            call_button_text.sections[0].value = "Call".to_string();
+        // The raise button and the raise size buttons
+        for mut raise_button_visibility in q_visibilities.p2().iter_mut() {
+            *raise_button_visibility = if raise_option.is_some() {
+                Visibility::Visible
+            } else {
+                Visibility::Hidden
+            };
         }
+
+        let mut q_call_button_text = q_texts.p1();
+        let mut call_button_text = q_call_button_text.single_mut();
+        call_button_text.sections[0].value = match call_option {
+            Action::Check => "Check".to_string(),
+            Action::AllIn(_) => "All-in".to_string(),
+            _ => "Call".to_string(),
+        };
+
         for mut move_button in q_move_buttons.iter_mut() {
             match *move_button {
-                MoveButtons::FoldButton => {}
+                MoveButtons::FoldButton | MoveButtons::RaiseSizeButton(_) => {}
                 MoveButtons::CallButton(_) => {
-                    *move_button = MoveButtons::CallButton(Chips(call_option as u32));
+                    *move_button = MoveButtons::CallButton(call_option);
                 }
                 MoveButtons::RaiseButton(_) => {
-                    *move_button = MoveButtons::RaiseButton(Chips(raise_option as u32));
+                    *move_button = MoveButtons::RaiseButton(raise_option);
                 }
             }
         }
-        let mut q_raise_button_text = q_texts.p0();
-        let mut raise_button_text = q_raise_button_text.single_mut();
-        raise_button_text.sections[0].value = format!(
-            "Raise {}",
-            MovedChips(Chips(raise_option as u32)).amount_str()
-        );
+        if let Some(raise_option) = raise_option {
+            let mut q_raise_button_text = q_texts.p0();
+            let mut raise_button_text = q_raise_button_text.single_mut();
+            raise_button_text.sections[0].value = match raise_option {
+                Action::Bet(to) => format!("Bet {}", MovedChips(to).amount_str(config.big_blind)),
+                Action::Raise(to) => {
+                    format!("Raise to {}", MovedChips(to).amount_str(config.big_blind))
+                }
+                _ => "All-in".to_string(),
+            };
+        }
     } else {
         *move_controls_visibility = Visibility::Hidden;
         let mut q_fold_button_visibility = q_visibilities.p1();
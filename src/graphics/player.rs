@@ -1,6 +1,6 @@
 use bevy::prelude::*;
 
-use lazy_static::lazy_static;
+use std::f32::consts::{FRAC_PI_2, PI};
 
 use super::{
     base_components::{
@@ -9,40 +9,48 @@ use super::{
     card::spawn_card,
     components::{
         BankrollText, CardBack, CardFace, CardRankText, DealerButtonText, DummyLabel,
-        HoleCardIndex, InActionIndicator, MovedChipsText, StackText,
+        HoleCardIndex, InActionIndicator, MovedChipsText, ShownHandText, StackText,
     },
     player_attributes::{PlayerAttributes, PlayerAttributesTuple},
 };
 use crate::table::{
-    components::{Amount, HoleCardsFaceUp, Player, SeatIndex},
+    components::{Amount, HoleCardsFaceUp, Player, PlayerIsHero, SeatIndex},
     events::TableUpdated,
+    resources::TableConfig,
 };
 
-lazy_static! {
-    static ref PLAYER_POSITIONS: Vec<Vec3> = vec![
-        Vec3::new(0.0, 140.0, 4.0),
-        Vec3::new(260.0, 70.0, 4.0),
-        Vec3::new(260.0, -70.0, 4.0),
-        Vec3::new(0.0, -140.0, 4.0),
-        Vec3::new(-260.0, -70.0, 4.0),
-        Vec3::new(-260.0, 70.0, 4.0),
-    ];
-    static ref FRONT_POSITIONS: Vec<Vec3> = vec![
-        Vec3::new(0.0, -60.0, 0.0),
-        Vec3::new(-120.0, -20.0, 0.0),
-        Vec3::new(-120.0, 40.0, 0.0),
-        Vec3::new(0.0, 80.0, 0.0),
-        Vec3::new(120.0, 40.0, 0.0),
-        Vec3::new(120.0, -20.0, 0.0),
-    ];
-    static ref BUTTON_POSITIONS: Vec<Vec3> = vec![
-        Vec3::new(70.0, 0.0, 0.0),
-        Vec3::new(70.0, 0.0, 0.0),
-        Vec3::new(70.0, 0.0, 0.0),
-        Vec3::new(-70.0, 0.0, 0.0),
-        Vec3::new(-70.0, 0.0, 0.0),
-        Vec3::new(-70.0, 0.0, 0.0),
-    ];
+/// Seats go clockwise around the table top, the hero's seat at the bottom
+fn seat_angle(seat_index: u8, n_seats: u8, hero_seat: u8) -> f32 {
+    let offset = seat_index as f32 - hero_seat as f32;
+    -FRAC_PI_2 - offset * 2.0 * PI / n_seats as f32
+}
+
+fn player_position(seat_index: u8, n_seats: u8, hero_seat: u8) -> Vec3 {
+    let angle = seat_angle(seat_index, n_seats, hero_seat);
+    Vec3::new(280.0 * angle.cos(), 140.0 * angle.sin(), 4.0)
+}
+
+/// Moved chips are shown towards the center of the table
+fn front_position(seat_index: u8, n_seats: u8, hero_seat: u8) -> Vec3 {
+    let angle = seat_angle(seat_index, n_seats, hero_seat);
+    Vec3::new(-120.0 * angle.cos(), -70.0 * angle.sin() + 10.0, 0.0)
+}
+
+/// Dealer button is on the side of the text box facing the top right
+fn button_position(seat_index: u8, n_seats: u8, hero_seat: u8) -> Vec3 {
+    let position = player_position(seat_index, n_seats, hero_seat);
+    if position.x > 1.0 || (position.x > -1.0 && position.y > 0.0) {
+        Vec3::new(70.0, 0.0, 0.0)
+    } else {
+        Vec3::new(-70.0, 0.0, 0.0)
+    }
+}
+
+/// Hole cards are fanned out above the text box, overlapping when there are four
+fn hole_card_position(index: usize, n_hole_cards: usize) -> Vec3 {
+    let spacing = if n_hole_cards > 2 { 20.0 } else { 30.0 };
+    let offset = index as f32 - (n_hole_cards - 1) as f32 / 2.0;
+    Vec3::new(offset * spacing, 43.0, 2.0 + index as f32 * 5.0)
 }
 
 pub fn spawn_players(
@@ -51,7 +59,10 @@ pub fn spawn_players(
     materials: &mut ResMut<Assets<ColorMaterial>>,
     asset_server: &Res<AssetServer>,
     players: &[PlayerAttributesTuple],
+    config: &TableConfig,
 ) -> Vec<Entity> {
+    let n_seats = config.n_seats;
+    let hero_seat = config.hero_seat();
     let mut player_ids = Vec::new();
     for fields in players {
         let player = PlayerAttributes::from(*fields);
@@ -61,7 +72,7 @@ pub fn spawn_players(
         let root = commands
             .spawn((
                 (*seat_index).clone(),
-                Container::new(PLAYER_POSITIONS[seat_index.0 as usize].clone()),
+                Container::new(player_position(seat_index.0, n_seats, hero_seat)),
             ))
             .id();
         let text_box = spawn_player_text_box(
@@ -87,7 +98,7 @@ pub fn spawn_players(
         let front = spawn_text(
             commands,
             asset_server,
-            FRONT_POSITIONS[seat_index.0 as usize].clone(),
+            front_position(seat_index.0, n_seats, hero_seat),
             "",
             16.0,
             "#ffffff",
@@ -97,43 +108,41 @@ pub fn spawn_players(
         let button = spawn_text(
             commands,
             asset_server,
-            BUTTON_POSITIONS[seat_index.0 as usize].clone(),
+            button_position(seat_index.0, n_seats, hero_seat),
             "D",
             20.0,
             "#ffff00",
             true,
             (*seat_index, DealerButtonText),
         );
-        let face_up = seat_index.0 == 3;
-        let hole_card_0 = spawn_card(
+        let shown_hand = spawn_text(
             commands,
-            meshes,
-            materials,
             asset_server,
-            &hole_cards.0[0],
-            Vec3::new(-15.0, 43.0, 2.0),
-            face_up,
-            (*seat_index, HoleCardIndex(0)),
-        );
-        let hole_card_1 = spawn_card(
-            commands,
-            meshes,
-            materials,
-            asset_server,
-            &hole_cards.0[1],
-            Vec3::new(15.0, 43.0, 2.0),
-            face_up,
-            (*seat_index, HoleCardIndex(1)),
+            Vec3::new(0.0, -42.0, 2.0),
+            "",
+            13.0,
+            "#ffffff",
+            true,
+            (*seat_index, ShownHandText),
         );
+        let face_up = player.is_hero.is_some();
+        let mut children = vec![text_box, in_action_indicator, front, button, shown_hand];
+        let n_hole_cards = hole_cards.0.len();
+        for (i, card) in hole_cards.0.iter().enumerate() {
+            let hole_card = spawn_card(
+                commands,
+                meshes,
+                materials,
+                asset_server,
+                card,
+                hole_card_position(i, n_hole_cards),
+                face_up,
+                (*seat_index, HoleCardIndex(i)),
+            );
+            children.push(hole_card);
+        }
 
-        commands.entity(root).push_children(&[
-            text_box,
-            in_action_indicator,
-            front,
-            button,
-            hole_card_0,
-            hole_card_1,
-        ]);
+        commands.entity(root).push_children(&children);
         player_ids.push(root);
     }
     player_ids
@@ -210,6 +219,7 @@ pub fn update_players(
         Query<(&mut Text, &SeatIndex), With<MovedChipsText>>,
         Query<(&mut Text, &SeatIndex), With<DealerButtonText>>,
         Query<(&mut Text, &SeatIndex, &HoleCardIndex), With<CardRankText>>,
+        Query<(&mut Text, &SeatIndex), With<ShownHandText>>,
     )>,
     mut q_visibilities: ParamSet<(
         Query<(&mut Visibility, &SeatIndex), With<InActionIndicator>>,
@@ -220,7 +230,8 @@ pub fn update_players(
         Query<(&mut Transform, &SeatIndex), With<CardBack>>,
     )>,
     q_players: Query<PlayerAttributesTuple, With<Player>>,
-    q_showdown: Query<(&SeatIndex, Option<&HoleCardsFaceUp>)>,
+    q_showdown: Query<(&SeatIndex, Option<&HoleCardsFaceUp>, Option<&PlayerIsHero>)>,
+    config: Res<TableConfig>,
 ) {
     let table_updated: bool = table_updated_event_reader.read().last().is_some();
     if !table_updated {
@@ -232,23 +243,33 @@ pub fn update_players(
         .map(|x| PlayerAttributes::from(*x))
         .collect();
     players.sort_by_key(|x| x.seat_index.0);
+    let n_players = players
+        .iter()
+        .filter(|player| player.is_eliminated.is_none())
+        .count() as u8;
 
     let mut q_bankroll_text = q_texts.p0();
     for (mut text, seat_index) in q_bankroll_text.iter_mut() {
         let player = &players[seat_index.0 as usize];
-        text.sections[0].value = player.bankroll.amount_str();
+        text.sections[0].value = player.bankroll.amount_str(config.big_blind);
     }
 
     let mut q_stack_text = q_texts.p1();
     for (mut text, seat_index) in q_stack_text.iter_mut() {
         let player = &players[seat_index.0 as usize];
-        text.sections[0].value = player.stack.amount_str();
+        text.sections[0].value = if player.is_eliminated.is_some() {
+            "Out".to_string()
+        } else if player.is_all_in.is_some() {
+            "All-in".to_string()
+        } else {
+            player.stack.amount_str(config.big_blind)
+        };
     }
 
     let mut q_moved_chips_text = q_texts.p2();
     for (mut text, seat_index) in q_moved_chips_text.iter_mut() {
         let player = &players[seat_index.0 as usize];
-        text.sections[0].value = player.moved_chips.amount_str();
+        text.sections[0].value = player.moved_chips.amount_str(config.big_blind);
     }
 
     let mut q_in_action_indicator_visibility = q_visibilities.p0();
@@ -274,7 +295,7 @@ pub fn update_players(
     let mut q_dealer_button_text = q_texts.p3();
     for (mut text, seat_index) in q_dealer_button_text.iter_mut() {
         let player = &players[seat_index.0 as usize];
-        if player.preflop_position.0 == 2 {
+        if player.is_eliminated.is_none() && player.preflop_position.is_button(n_players) {
             text.sections[0].value = "D".to_string();
         } else {
             text.sections[0].value = "".to_string();
@@ -289,8 +310,17 @@ pub fn update_players(
         text.sections[0].style.color = Color::hex(card.suit_color()).unwrap();
     }
 
-    for (seat_index, shown) in q_showdown.iter() {
-        if seat_index.0 == 3 {
+    let mut q_shown_hand_text = q_texts.p5();
+    for (mut text, seat_index) in q_shown_hand_text.iter_mut() {
+        let player = &players[seat_index.0 as usize];
+        text.sections[0].value = match player.shown_hand {
+            Some(shown_hand) => shown_hand.0.clone(),
+            None => "".to_string(),
+        };
+    }
+
+    for (seat_index, shown, is_hero) in q_showdown.iter() {
+        if is_hero.is_some() {
             continue;
         }
         if shown.is_some() {
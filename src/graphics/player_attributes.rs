@@ -1,6 +1,6 @@
 use crate::table::components::{
-    Bankroll, HoleCards, HoleCardsHidden, MovedChips, PlayerInAction, PlayerIsActive, PlayerIsHero,
-    PlayerName, PreflopPosition, SeatIndex, Stack,
+    Bankroll, HoleCards, MovedChips, PlayerInAction, PlayerIsActive, PlayerIsAllIn,
+    PlayerIsEliminated, PlayerIsHero, PlayerName, PreflopPosition, SeatIndex, ShownHand, Stack,
 };
 
 pub struct PlayerAttributes<'a> {
@@ -14,7 +14,9 @@ pub struct PlayerAttributes<'a> {
     pub is_active: Option<&'a PlayerIsActive>,
     pub is_hero: Option<&'a PlayerIsHero>,
     pub in_action: Option<&'a PlayerInAction>,
-    pub hole_cards_hidden: Option<&'a HoleCardsHidden>,
+    pub is_all_in: Option<&'a PlayerIsAllIn>,
+    pub is_eliminated: Option<&'a PlayerIsEliminated>,
+    pub shown_hand: Option<&'a ShownHand>,
 }
 
 impl From<PlayerAttributesTuple<'_>> for PlayerAttributes<'_> {
@@ -34,5 +36,7 @@ pub type PlayerAttributesTuple<'a> = (
     Option<&'a PlayerIsActive>,
     Option<&'a PlayerIsHero>,
     Option<&'a PlayerInAction>,
-    Option<&'a HoleCardsHidden>,
+    Option<&'a PlayerIsAllIn>,
+    Option<&'a PlayerIsEliminated>,
+    Option<&'a ShownHand>,
 );
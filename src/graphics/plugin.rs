@@ -6,12 +6,13 @@ use super::{
     board::update_board,
     components::MainCamera,
     events::InfoMessageEvent,
-    move_controls::{handle_clicks_on_move_buttons, update_move_controls},
+    move_controls::{handle_clicks_on_move_buttons, update_move_controls, RaiseSize},
     pause::toggle_pause,
     player::update_players,
     table_ui::{setup_table_ui, show_table_ui},
+    tournament_results::show_tournament_results,
 };
-use crate::table::{self, events::TableUpdated};
+use crate::table::{self, events::TableUpdated, states::HandoutState};
 
 pub struct TableUiPlugin;
 
@@ -20,6 +21,7 @@ impl Plugin for TableUiPlugin {
         app.add_event::<InfoMessageEvent>();
 
         app.init_resource::<ButtonInput<MouseButton>>();
+        app.init_resource::<RaiseSize>();
 
         app.add_systems(
             Startup,
@@ -41,6 +43,10 @@ impl Plugin for TableUiPlugin {
             )
                 .chain(),
         );
+        app.add_systems(
+            OnEnter(HandoutState::TournamentFinished),
+            show_tournament_results,
+        );
     }
 }
 
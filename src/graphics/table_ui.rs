@@ -1,6 +1,6 @@
-use bevy::prelude::*;
+use bevy::{ecs::system::SystemParam, prelude::*};
 
-use crate::table::{components::Player, events::TableUpdated};
+use crate::table::{components::Player, events::TableUpdated, resources::TableConfig};
 
 use super::{
     base_components::{spawn_text, Container, MaterialMesh},
@@ -14,14 +14,21 @@ use super::{
 
 const TABLE_TOP_Y: f32 = 70.0;
 
+/// Players and board the table is drawn from
+#[derive(SystemParam)]
+pub struct TableEntities<'w, 's> {
+    players: Query<'w, 's, PlayerAttributesTuple<'static>, With<Player>>,
+    board: Query<'w, 's, BoardAttributes<'static>>,
+}
+
 pub fn setup_table_ui(
     mut commands: Commands,
     mut meshes: ResMut<Assets<Mesh>>,
     mut color_materials: ResMut<Assets<ColorMaterial>>,
     mut table_updated_event_writer: EventWriter<TableUpdated>,
     asset_server: Res<AssetServer>,
-    q_players: Query<PlayerAttributesTuple, With<Player>>,
-    q_board: Query<BoardAttributes>,
+    config: Res<TableConfig>,
+    table_entities: TableEntities,
 ) {
     let background = spawn_background(&mut commands, &mut meshes, &mut color_materials);
     let table_top = spawn_table_top(&mut commands, &mut meshes, &mut color_materials);
@@ -44,15 +51,16 @@ pub fn setup_table_ui(
         .entity(background)
         .push_children(&[table_top, pause_button]);
 
-    let players: Vec<PlayerAttributesTuple> = q_players.iter().collect();
+    let players: Vec<PlayerAttributesTuple> = table_entities.players.iter().collect();
     let mut children: Vec<Entity> = spawn_players(
         &mut commands,
         &mut meshes,
         &mut color_materials,
         &asset_server,
         &players,
+        &config,
     );
-    let board: Vec<BoardAttributes> = q_board.iter().collect();
+    let board: Vec<BoardAttributes> = table_entities.board.iter().collect();
     let board_id = spawn_board(
         &mut commands,
         &mut meshes,
@@ -0,0 +1,71 @@
+use bevy::prelude::*;
+
+use crate::table::resources::{GameStateResource, TableConfig};
+
+use super::{
+    base_components::{spawn_rounded_rectangle_with_border, spawn_text},
+    components::{MoveControls, TournamentResults},
+};
+
+pub fn show_tournament_results(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut q_move_controls_visibility: Query<&mut Visibility, With<MoveControls>>,
+    asset_server: Res<AssetServer>,
+    game: Res<GameStateResource>,
+    config: Res<TableConfig>,
+) {
+    let Some(tournament_state) = &game.state.tournament else {
+        return;
+    };
+    *q_move_controls_visibility.single_mut() = Visibility::Hidden;
+
+    let line_height = 24.0;
+    let height = 60.0 + line_height * tournament_state.results.len() as f32;
+    let root = spawn_rounded_rectangle_with_border(
+        &mut commands,
+        &mut materials,
+        &mut meshes,
+        Vec3::new(0.0, 70.0, 50.0),
+        Vec2::new(360.0, height),
+        "#333333",
+        8.0,
+        "#eeeeee",
+        2.0,
+        TournamentResults,
+    );
+    let top = height / 2.0 - 24.0;
+    let mut children = vec![spawn_text(
+        &mut commands,
+        &asset_server,
+        Vec3::new(0.0, top, 3.0),
+        "Results",
+        20.0,
+        "#ffff00",
+        true,
+        TournamentResults,
+    )];
+    for (i, result) in tournament_state.results.iter().enumerate() {
+        let payout = if result.payout > 0 {
+            format!("${}", result.payout)
+        } else {
+            "".to_string()
+        };
+        children.push(spawn_text(
+            &mut commands,
+            &asset_server,
+            Vec3::new(0.0, top - line_height * (i as f32 + 1.5), 3.0),
+            &format!("{}. {}  {}", result.place, result.name, payout),
+            16.0,
+            if result.seat_index == config.hero_seat() {
+                "#ffff00"
+            } else {
+                "#ffffff"
+            },
+            false,
+            TournamentResults,
+        ));
+    }
+    commands.entity(root).push_children(&children);
+}
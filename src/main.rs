@@ -1,12 +1,54 @@
 use bevy::{prelude::*, window::WindowResolution};
-use graphics::TableUiPlugin;
-use table::{states::PausedState, TablePlugin};
-
-mod graphics;
-mod table;
+use poker_simulator::{
+    graphics::TableUiPlugin,
+    table::{
+        resources::{BettingStructure, ScenarioResource, TableConfig},
+        scenario::Scenario,
+        states::PausedState,
+        tournament::TournamentConfig,
+        TablePlugin,
+    },
+};
 
 fn main() {
-    App::new()
+    let args: Vec<String> = std::env::args().collect();
+    let mut config = if args.iter().any(|arg| arg == "--omaha") {
+        TableConfig::pot_limit_omaha()
+    } else if args.iter().any(|arg| arg == "--short-deck") {
+        TableConfig::short_deck()
+    } else {
+        TableConfig::default()
+    };
+    if args.iter().any(|arg| arg == "--limit") {
+        config.betting_structure = BettingStructure::Limit;
+    }
+    if args.iter().any(|arg| arg == "--sit-and-go") {
+        config.tournament = Some(TournamentConfig::sit_and_go(config.n_seats));
+    }
+    if let Some(seed_index) = args.iter().position(|arg| arg == "--seed") {
+        config.seed = Some(
+            args.get(seed_index + 1)
+                .and_then(|seed| seed.parse().ok())
+                .expect("--seed takes an unsigned integer"),
+        );
+    }
+    let scenario = args.iter().position(|arg| arg == "--scenario").map(|path_index| {
+        let path = args.get(path_index + 1).expect("--scenario takes a file path");
+        let scenario = Scenario::load(path).and_then(|scenario| {
+            let mut config = config.clone();
+            scenario.configure(&mut config);
+            scenario.validate(&config).map(|_| scenario)
+        });
+        scenario.unwrap_or_else(|error| {
+            eprintln!("Invalid scenario {}: {}", path, error);
+            std::process::exit(1);
+        })
+    });
+    let mut app = App::new();
+    if let Some(scenario) = scenario {
+        app.insert_resource(ScenarioResource { scenario });
+    }
+    app.insert_resource(config)
         .add_plugins((
             DefaultPlugins.set(WindowPlugin {
                 primary_window: Some(Window {
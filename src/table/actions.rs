@@ -0,0 +1,227 @@
+use std::fmt;
+
+use super::components::Chips;
+
+/// A player's decision.
+///
+/// [[Action::Call]] and [[Action::AllIn]] carry the chips the player adds to the front.
+/// [[Action::Bet]] and [[Action::Raise]] carry the total front the player bets to on this street.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    Fold,
+    Check,
+    Call(Chips),
+    Bet(Chips),
+    Raise(Chips),
+    AllIn(Chips),
+}
+
+impl Action {
+    /// Chips moved from the stack by this action for a player who already has `front` in front
+    pub fn chips_to_move(&self, front: u32) -> u32 {
+        match self {
+            Action::Fold | Action::Check => 0,
+            Action::Call(chips) | Action::AllIn(chips) => chips.0,
+            Action::Bet(chips) | Action::Raise(chips) => chips.0.saturating_sub(front),
+        }
+    }
+}
+
+impl fmt::Display for Action {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Action::Fold => write!(f, "fold"),
+            Action::Check => write!(f, "check"),
+            Action::Call(chips) => write!(f, "call {}", chips.0),
+            Action::Bet(chips) => write!(f, "bet to {}", chips.0),
+            Action::Raise(chips) => write!(f, "raise to {}", chips.0),
+            Action::AllIn(chips) => write!(f, "all-in {}", chips.0),
+        }
+    }
+}
+
+/// Allowed totals for a bet or a raise, both inclusive
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RaiseBounds {
+    pub min_to: Chips,
+    pub max_to: Chips,
+}
+
+/// Everything the player in action may do
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LegalActions {
+    /// Chips already in front of the player on this street
+    pub front: Chips,
+    pub stack: Chips,
+    /// Chips to add to call; [[None]] when there is nothing to call
+    pub to_call: Option<Chips>,
+    /// [[None]] when the player cannot bet or raise. `max_to` covering the whole stack means
+    /// the largest raise is all-in.
+    pub raise: Option<RaiseBounds>,
+    /// Whether a bet or a raise would be the first chips in on this street
+    pub is_opening_bet: bool,
+}
+
+impl LegalActions {
+    pub fn can_check(&self) -> bool {
+        self.to_call.is_none()
+    }
+
+    pub fn can_fold(&self) -> bool {
+        self.to_call.is_some()
+    }
+
+    /// Bet or raise to `to`, whichever applies on this street
+    pub fn raise_action(&self, to: Chips) -> Action {
+        if to.0 >= self.front.0 + self.stack.0 {
+            Action::AllIn(self.stack)
+        } else if self.is_opening_bet {
+            Action::Bet(to)
+        } else {
+            Action::Raise(to)
+        }
+    }
+
+    /// Passive options first, then the smallest raise if any
+    pub fn options(&self) -> Vec<Action> {
+        let mut options = Vec::new();
+        match self.to_call {
+            None => options.push(Action::Check),
+            Some(to_call) => {
+                options.push(Action::Fold);
+                if to_call.0 >= self.stack.0 {
+                    options.push(Action::AllIn(self.stack));
+                } else {
+                    options.push(Action::Call(to_call));
+                }
+            }
+        }
+        if let Some(raise) = &self.raise {
+            options.push(self.raise_action(raise.min_to));
+        }
+        options
+    }
+
+    pub fn validate(&self, action: &Action) -> Result<(), ActionError> {
+        match *action {
+            Action::Fold => {
+                if self.can_fold() {
+                    Ok(())
+                } else {
+                    Err(ActionError::NothingToFoldTo)
+                }
+            }
+            Action::Check => match self.to_call {
+                None => Ok(()),
+                Some(to_call) => Err(ActionError::CannotCheck { to_call }),
+            },
+            Action::Call(chips) => match self.to_call {
+                None => Err(ActionError::NothingToCall),
+                Some(to_call) if chips != to_call => Err(ActionError::WrongCallAmount {
+                    expected: to_call,
+                    got: chips,
+                }),
+                Some(_) => Ok(()),
+            },
+            Action::Bet(to) | Action::Raise(to) => {
+                if self.is_opening_bet != matches!(action, Action::Bet(_)) {
+                    return Err(ActionError::WrongAggressiveAction);
+                }
+                match &self.raise {
+                    None => Err(ActionError::RaiseNotAllowed),
+                    Some(bounds) if to.0 < bounds.min_to.0 || to.0 > bounds.max_to.0 => {
+                        Err(ActionError::RaiseOutOfBounds {
+                            bounds: *bounds,
+                            got: to,
+                        })
+                    }
+                    Some(_) => {
+                        if to.0 >= self.front.0 + self.stack.0 {
+                            Err(ActionError::ShouldBeAllIn)
+                        } else {
+                            Ok(())
+                        }
+                    }
+                }
+            }
+            Action::AllIn(chips) => {
+                if chips != self.stack || chips.0 == 0 {
+                    return Err(ActionError::WrongAllInAmount {
+                        expected: self.stack,
+                        got: chips,
+                    });
+                }
+                let is_call = matches!(self.to_call, Some(to_call) if to_call.0 >= chips.0);
+                let is_max_raise = matches!(
+                    &self.raise,
+                    Some(bounds) if bounds.max_to.0 >= self.front.0 + self.stack.0
+                );
+                if is_call || is_max_raise {
+                    Ok(())
+                } else {
+                    Err(ActionError::RaiseNotAllowed)
+                }
+            }
+        }
+    }
+}
+
+/// Why an action was rejected
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ActionError {
+    /// Nobody is to act: the betting round is over
+    NotInAction,
+    NothingToFoldTo,
+    CannotCheck {
+        to_call: Chips,
+    },
+    NothingToCall,
+    WrongCallAmount {
+        expected: Chips,
+        got: Chips,
+    },
+    /// Bet when facing a bet, or raise when there is nothing to raise
+    WrongAggressiveAction,
+    RaiseNotAllowed,
+    RaiseOutOfBounds {
+        bounds: RaiseBounds,
+        got: Chips,
+    },
+    /// Bet or raise for the whole stack must be [[Action::AllIn]]
+    ShouldBeAllIn,
+    WrongAllInAmount {
+        expected: Chips,
+        got: Chips,
+    },
+}
+
+impl fmt::Display for ActionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ActionError::NotInAction => write!(f, "no player is in action"),
+            ActionError::NothingToFoldTo => write!(f, "nothing to fold to, check instead"),
+            ActionError::CannotCheck { to_call } => {
+                write!(f, "cannot check facing {} to call", to_call.0)
+            }
+            ActionError::NothingToCall => write!(f, "nothing to call"),
+            ActionError::WrongCallAmount { expected, got } => {
+                write!(f, "call must be {}, got {}", expected.0, got.0)
+            }
+            ActionError::WrongAggressiveAction => {
+                write!(f, "bet only opens a street, raise only answers a bet")
+            }
+            ActionError::RaiseNotAllowed => write!(f, "raising is not allowed"),
+            ActionError::RaiseOutOfBounds { bounds, got } => write!(
+                f,
+                "raise must be to {}..={}, got {}",
+                bounds.min_to.0, bounds.max_to.0, got.0
+            ),
+            ActionError::ShouldBeAllIn => write!(f, "betting the whole stack is all-in"),
+            ActionError::WrongAllInAmount { expected, got } => {
+                write!(f, "all-in must be {}, got {}", expected.0, got.0)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ActionError {}
@@ -1,30 +1,40 @@
-use bevy::prelude::*;
+use super::actions::{Action, LegalActions, RaiseBounds};
+use super::components::{Chips, LastFullRaise};
+use super::engine::GameState;
+use super::resources::{BettingStructure, TableConfig};
 
-use super::components::{MovedChips, PlayerInAction, PlayerIsActive, PreflopPosition, Stack};
-
-pub type PlayerAttributes<'a> = (
-    Entity,
-    &'a PreflopPosition,
-    &'a Stack,
-    &'a MovedChips,
-    Option<&'a PlayerIsActive>,
-    Option<&'a PlayerInAction>,
-);
+/// Bets and raises allowed on a street in fixed-limit, the big blind counting as the first
+pub const MAX_BETS_PER_STREET: u32 = 4;
 
+/// Betting round of the current street, players sorted in the order they act
 pub struct Betting {
     players: Vec<Player>,
     pot: u32,
-    street: u8,
+    last_full_raise: LastFullRaise,
+    betting_structure: BettingStructure,
+    /// Bet and raise size in fixed-limit
+    limit_bet_size: u32,
 }
 
 impl Betting {
-    pub fn new(query_result: &[PlayerAttributes], pot: u32, street: u8) -> Self {
-        let mut players: Vec<Player> = query_result.iter().map(Player::from).collect();
-        let action_order = if street == 0 {
-            PREFLOP_ACTION_ORDER
-        } else {
-            POSTFLOP_ACTION_ORDER
-        };
+    pub fn new(state: &GameState) -> Self {
+        let mut players: Vec<Player> = state
+            .seats
+            .iter()
+            .enumerate()
+            .filter(|(_, seat)| !seat.is_eliminated)
+            .map(|(seat_index, seat)| Player {
+                seat_index,
+                position: seat.preflop_position,
+                stack: seat.stack.0,
+                front: seat.front.0,
+                is_active: seat.is_active,
+                in_action: state.in_action == Some(seat_index),
+                is_all_in: seat.is_all_in,
+                has_acted: seat.has_acted,
+            })
+            .collect();
+        let action_order = action_order(state.street, players.len() as u8, &state.config);
         players.sort_by_key(|player| {
             let position = player.position;
             let index = action_order.iter().position(|&x| x == position).unwrap();
@@ -32,18 +42,20 @@ impl Betting {
         });
         Self {
             players,
-            pot,
-            street,
+            pot: state.pot.0,
+            last_full_raise: state.last_full_raise,
+            betting_structure: state.config.betting_structure,
+            limit_bet_size: state.config.limit_bet_size(state.street).0,
         }
     }
 
-    pub fn set_actor_inactive(&mut self) {
-        let actor = self
-            .players
-            .iter_mut()
-            .find(|player| player.in_action)
-            .unwrap();
-        actor.is_active = false;
+    /// Whether at least two players can still put chips in
+    pub fn is_betting_possible(&self) -> bool {
+        self.players
+            .iter()
+            .filter(|player| player.can_act())
+            .count()
+            >= 2
     }
 
     pub fn max_front(&self) -> u32 {
@@ -60,23 +72,92 @@ impl Betting {
         (max_front - actor.front).min(actor.stack)
     }
 
-    pub fn move_options(&self) -> Vec<i32> {
+    /// Options of the player in action.
+    ///
+    /// The minimum raise is the last full raise increment. The maximum is all-in in no-limit,
+    /// a pot-sized raise in pot-limit. In fixed-limit the only raise is one bet, until the cap.
+    /// An all-in for less than a full raise does not reopen the action for players who have
+    /// already acted on the last full raise: they may only call or fold.
+    pub fn legal_actions(&self) -> LegalActions {
         let max_front = self.max_front();
         let actor = self.players.iter().find(|player| player.in_action).unwrap();
         let tocall: u32 = (max_front - actor.front).min(actor.stack);
-        let raise_amount: u32 = if self.street == 0 {
-            (self.pot * 2).min(actor.stack)
-        } else {
-            (self.pot as f32 * 0.5) as u32
+        let all_in_to = actor.front + actor.stack;
+        let min_to = (max_front + self.min_raise_increment()).min(all_in_to);
+        let max_to = match self.betting_structure {
+            BettingStructure::NoLimit => all_in_to,
+            BettingStructure::PotLimit => all_in_to.min(self.pot_raise_to(1.0)),
+            BettingStructure::Limit => min_to,
         };
-        if tocall == 0 {
-            vec![0, raise_amount as i32]
+        let has_opponent_to_raise = self
+            .players
+            .iter()
+            .any(|player| !player.in_action && player.can_act());
+        let is_action_reopened = !actor.has_acted || actor.front < self.last_full_raise.to.0;
+        let is_capped = self.betting_structure == BettingStructure::Limit
+            && self.last_full_raise.to.0 / self.limit_bet_size >= MAX_BETS_PER_STREET;
+        let raise =
+            if actor.stack > tocall && has_opponent_to_raise && is_action_reopened && !is_capped {
+                let min_to = min_to.min(max_to);
+                Some(RaiseBounds {
+                    min_to: Chips(min_to),
+                    max_to: Chips(max_to),
+                })
+            } else {
+                None
+            };
+        LegalActions {
+            front: Chips(actor.front),
+            stack: Chips(actor.stack),
+            to_call: if tocall > 0 {
+                Some(Chips(tocall))
+            } else {
+                None
+            },
+            raise,
+            is_opening_bet: max_front == 0,
+        }
+    }
+
+    /// Smallest full raise over the current front level
+    fn min_raise_increment(&self) -> u32 {
+        match self.betting_structure {
+            BettingStructure::NoLimit | BettingStructure::PotLimit => {
+                self.last_full_raise.increment.0
+            }
+            BettingStructure::Limit => self.limit_bet_size,
+        }
+    }
+
+    /// Total to raise to for a raise of `fraction` of the pot after calling
+    pub fn pot_raise_to(&self, fraction: f32) -> u32 {
+        let max_front = self.max_front();
+        let pot_after_call = self.pot + self.tocall();
+        max_front + (pot_after_call as f32 * fraction) as u32
+    }
+
+    /// [[LastFullRaise]] once the player in action has made `action`
+    pub fn last_full_raise_after(&self, action: &Action) -> LastFullRaise {
+        let max_front = self.max_front();
+        let actor = self.players.iter().find(|player| player.in_action).unwrap();
+        let new_front = actor.front + action.chips_to_move(actor.front).min(actor.stack);
+        if new_front <= max_front {
+            return self.last_full_raise;
+        }
+        let increment = new_front - max_front;
+        if increment >= self.min_raise_increment() {
+            LastFullRaise {
+                to: Chips(new_front),
+                increment: Chips(increment),
+            }
         } else {
-            vec![-1, tocall as i32, raise_amount as i32]
+            // Incomplete all-in raise
+            self.last_full_raise
         }
     }
 
-    pub fn next_actor_id(&self) -> Option<Entity> {
+    /// Seat index of the next player to act after the player in action, if any
+    pub fn next_actor(&self) -> Option<usize> {
         let n_active_players = self
             .players
             .iter()
@@ -85,85 +166,205 @@ impl Betting {
         if n_active_players < 2 {
             return None;
         }
-        let current_actor_index: Option<usize> =
-            self.players.iter().position(|player| player.in_action);
-        if current_actor_index.is_none() {
-            for player in self.players.iter() {
-                if player.is_active {
-                    return Some(player.entity);
-                }
-            }
-            return None;
-        }
-        let current_actor_index: usize = current_actor_index.unwrap();
-        let mut next_potential_actor: Option<&Player> = None;
-        let mut next_potential_actor_index: usize = 0;
-        for i in 1..6 {
-            let index = (current_actor_index + i) % 6;
-            let player = &self.players[index];
-            if player.is_active {
-                next_potential_actor = Some(player);
-                next_potential_actor_index = index;
-                break;
-            }
-        }
-        if next_potential_actor.is_none() {
-            return None;
-        }
-        let next_potential_actor: &Player = next_potential_actor.unwrap();
-
+        let n_players_able_to_act = self
+            .players
+            .iter()
+            .filter(|player| player.can_act())
+            .count();
         let max_front = self.max_front();
-        let tocall = (max_front - next_potential_actor.front).min(next_potential_actor.stack);
-
-        if tocall == 0 {
-            // Determining if potential player has acted
-            if max_front > 0 {
-                if self.street == 0 && max_front == 2 && next_potential_actor.position == 0 {
-                    // bb not acted yet
-                    return Some(next_potential_actor.entity);
-                } else {
-                    return None;
-                }
-            } else {
-                let is_next_potential_player_first_in_action_order = next_potential_actor_index
-                    == 0
-                    || self.players[..next_potential_actor_index]
-                        .iter()
-                        .all(|player| !player.is_active);
-                if is_next_potential_player_first_in_action_order {
-                    return None;
-                } else {
-                    return Some(next_potential_actor.entity);
-                }
+        let n_players = self.players.len();
+        let first_index: usize = match self.players.iter().position(|player| player.in_action) {
+            Some(current_actor_index) => current_actor_index + 1,
+            None => 0,
+        };
+        for i in 0..n_players {
+            let player = &self.players[(first_index + i) % n_players];
+            if player.in_action || !player.can_act() {
+                continue;
+            }
+            let faces_bet = player.front < max_front;
+            // With everybody else all-in, there is nothing to respond to unless facing a bet
+            let has_option = !player.has_acted && n_players_able_to_act >= 2;
+            if faces_bet || has_option {
+                return Some(player.seat_index);
             }
         }
-        return Some(next_potential_actor.entity);
+        None
     }
 }
 
 #[derive(Debug)]
 struct Player {
-    entity: Entity,
+    seat_index: usize,
     position: u8,
     stack: u32,
     front: u32,
     is_active: bool,
     in_action: bool,
+    is_all_in: bool,
+    has_acted: bool,
 }
 
-impl<'a> From<&PlayerAttributes<'a>> for Player {
-    fn from(query: &PlayerAttributes) -> Self {
-        let (entity, position, stack, moved_chips, is_active, in_action) = query;
-        Self {
-            entity: entity.clone(),
-            position: position.0,
-            stack: stack.0 .0,
-            front: moved_chips.0 .0,
-            is_active: is_active.is_some(),
-            in_action: in_action.is_some(),
+impl Player {
+    fn can_act(&self) -> bool {
+        self.is_active && !self.is_all_in
+    }
+}
+
+/// Preflop positions in the order players act on the street.
+///
+/// On 6-max: `[5, 4, 3, 2, 1, 0]` preflop and `[1, 0, 5, 4, 3, 2]` postflop. Heads-up the small
+/// blind is on the button: first preflop, last postflop. A straddler acts last preflop.
+pub fn action_order(street: u8, n_players: u8, config: &TableConfig) -> Vec<u8> {
+    if street == 0 {
+        let mut order: Vec<u8> = (0..n_players).rev().collect();
+        if config.straddle(n_players).is_some() {
+            order.rotate_left(1);
         }
+        order
+    } else if n_players == 2 {
+        vec![0, 1]
+    } else {
+        let mut order = vec![1, 0];
+        order.extend((2..n_players).rev());
+        order
     }
 }
 
-const PREFLOP_ACTION_ORDER: [u8; 6] = [5, 4, 3, 2, 1, 0];
-const POSTFLOP_ACTION_ORDER: [u8; 6] = [1, 0, 5, 4, 3, 2];
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::table::actions::ActionError;
+    use crate::table::engine::Phase;
+    use crate::table::scenario::Scenario;
+
+    const BUTTON: usize = 0;
+    const SMALL_BLIND: usize = 1;
+    const BIG_BLIND: usize = 2;
+
+    /// Blinds 1/2, seats 0, 1 and 2 on the button, small blind and big blind
+    fn three_handed(betting_structure: BettingStructure, stacks: [u32; 3]) -> GameState {
+        let config = TableConfig {
+            n_seats: 3,
+            betting_structure,
+            seed: Some(0),
+            ..Default::default()
+        };
+        let scenario = Scenario {
+            button_seat: Some(BUTTON as u8),
+            stacks: (0..3).map(|i| (i as u8, Chips(stacks[i]))).collect(),
+            ..Default::default()
+        };
+        let names: Vec<String> = (0..3).map(|i| format!("Player {}", i)).collect();
+        let state = GameState::from_scenario(config, &names, &scenario).unwrap();
+        assert_eq!(state.in_action, Some(BUTTON));
+        state
+    }
+
+    fn act(state: &mut GameState, actions: &[Action]) {
+        for &action in actions {
+            state.apply_action(action).unwrap();
+        }
+    }
+
+    #[test]
+    fn all_in_runs_the_board_out() {
+        let mut state = three_handed(BettingStructure::NoLimit, [100, 200, 400]);
+        act(
+            &mut state,
+            &[
+                Action::AllIn(Chips(100)),
+                Action::Fold,
+                Action::Call(Chips(98)),
+            ],
+        );
+        // The big blind covers the button, nobody is left to bet against
+        assert!(state.seats[BUTTON].is_all_in);
+        assert!(!state.seats[BIG_BLIND].is_all_in);
+        assert_eq!(state.in_action, None);
+        while state.phase != Phase::HandoutEnded {
+            assert_ne!(state.phase, Phase::Betting);
+            state.advance();
+        }
+        assert!(state.board.iter().all(|card| card.is_defined()));
+        let total: u32 = state
+            .seats
+            .iter()
+            .map(|seat| seat.stack.0 + seat.front.0)
+            .sum();
+        assert_eq!(total, 700);
+        assert_eq!(state.seats[SMALL_BLIND].stack, Chips(199));
+    }
+
+    fn raise_bounds(state: &GameState) -> Option<(u32, u32)> {
+        let raise = state.legal_actions().unwrap().raise?;
+        Some((raise.min_to.0, raise.max_to.0))
+    }
+
+    #[test]
+    fn min_raise_is_the_last_full_raise() {
+        let mut state = three_handed(BettingStructure::NoLimit, [200, 200, 200]);
+        assert_eq!(raise_bounds(&state), Some((4, 200)));
+        act(&mut state, &[Action::Raise(Chips(10))]);
+        assert_eq!(state.last_full_raise.increment, Chips(8));
+        assert_eq!(raise_bounds(&state), Some((18, 200)));
+        assert!(matches!(
+            state.apply_action(Action::Raise(Chips(17))),
+            Err(ActionError::RaiseOutOfBounds { .. })
+        ));
+        act(&mut state, &[Action::Raise(Chips(30))]);
+        assert_eq!(raise_bounds(&state), Some((50, 200)));
+    }
+
+    #[test]
+    fn incomplete_all_in_does_not_reopen_the_action() {
+        let mut state = three_handed(BettingStructure::NoLimit, [200, 15, 200]);
+        act(
+            &mut state,
+            &[Action::Raise(Chips(10)), Action::AllIn(Chips(14))],
+        );
+        // Raising by 5 is less than the full raise of 8
+        assert_eq!(state.last_full_raise.to, Chips(10));
+        // The big blind has not acted yet and may still raise
+        assert_eq!(raise_bounds(&state), Some((23, 200)));
+        act(&mut state, &[Action::Call(Chips(13))]);
+        assert_eq!(state.in_action, Some(BUTTON));
+        let legal_actions = state.legal_actions().unwrap();
+        assert_eq!(legal_actions.to_call, Some(Chips(5)));
+        assert!(legal_actions.raise.is_none());
+        assert_eq!(
+            state.apply_action(Action::Raise(Chips(30))),
+            Err(ActionError::RaiseNotAllowed)
+        );
+    }
+
+    #[test]
+    fn limit_raises_are_capped() {
+        let mut state = three_handed(BettingStructure::Limit, [200, 200, 200]);
+        assert_eq!(raise_bounds(&state), Some((4, 4)));
+        act(
+            &mut state,
+            &[Action::Raise(Chips(4)), Action::Raise(Chips(6))],
+        );
+        assert_eq!(raise_bounds(&state), Some((8, 8)));
+        act(&mut state, &[Action::Raise(Chips(8))]);
+        // The big blind and three raises make the four bets
+        let legal_actions = state.legal_actions().unwrap();
+        assert_eq!(legal_actions.to_call, Some(Chips(4)));
+        assert!(legal_actions.raise.is_none());
+    }
+
+    #[test]
+    fn pot_limit_raises_up_to_the_pot() {
+        let mut state = three_handed(BettingStructure::PotLimit, [200, 200, 200]);
+        // Calling 2 makes a pot of 5, raised on top of the call
+        assert_eq!(raise_bounds(&state), Some((4, 7)));
+        assert!(matches!(
+            state.apply_action(Action::Raise(Chips(8))),
+            Err(ActionError::RaiseOutOfBounds { .. })
+        ));
+        act(&mut state, &[Action::Raise(Chips(7))]);
+        // Calling 6 makes a pot of 16
+        assert_eq!(raise_bounds(&state), Some((12, 23)));
+    }
+}
@@ -0,0 +1,225 @@
+use super::{
+    components::{BoardCards, Card},
+    draws::category,
+    hand_evaluation::Rank,
+    made_hand::best_hand,
+    resources::GameVariant,
+};
+
+/// How the ranks of the board repeat
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Pairing {
+    Unpaired,
+    Paired,
+    TwoPaired,
+    Trips,
+    FullHouse,
+    Quads,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Suitedness {
+    /// No two cards of a suit
+    Rainbow,
+    /// Two suits, or a turn or river with two cards of a suit
+    TwoTone,
+    /// All cards of one suit
+    Monotone,
+}
+
+/// Class of the top card of the board
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum HighCardClass {
+    /// Up to six
+    Low,
+    /// Seven to nine
+    Middle,
+    /// Ten to king
+    Broadway,
+    Ace,
+}
+
+/// Features of a flop, turn or river board shared by bots and study tools
+#[derive(Debug, Clone)]
+pub struct BoardTexture {
+    pub pairing: Pairing,
+    pub suitedness: Suitedness,
+    /// Most cards of one suit
+    pub max_suit_count: u8,
+    /// Ranks missing between neighbouring distinct ranks, highest first: 0 between
+    /// connected cards
+    pub gaps: Vec<u8>,
+    pub high_card: HighCardClass,
+    /// Some hole cards make a straight
+    pub straight_possible: bool,
+    /// Some hole cards make a flush
+    pub flush_possible: bool,
+    /// Best hand any hole cards make
+    pub nut_hand: Rank,
+    /// Two-card combos making [[Self::nut_hand]]. In Omaha, the pairs of hole cards a hand
+    /// plays.
+    pub n_nut_combos: usize,
+}
+
+impl BoardCards {
+    /// [[None]] before the flop
+    pub fn texture(&self, variant: GameVariant) -> Option<BoardTexture> {
+        let board: Vec<Card> = self
+            .0
+            .iter()
+            .filter(|card| card.is_defined())
+            .cloned()
+            .collect();
+        board_texture(&board, variant)
+    }
+}
+
+/// Texture of 3 to 5 `board` cards, [[None]] with fewer or more
+pub fn board_texture(board: &[Card], variant: GameVariant) -> Option<BoardTexture> {
+    if !(3..=5).contains(&board.len()) {
+        return None;
+    }
+    let mut rank_counts = [0u8; 13];
+    let mut suit_counts = [0u8; 4];
+    for card in board {
+        rank_counts[card.rank() as usize] += 1;
+        suit_counts[card.suit() as usize] += 1;
+    }
+
+    let n_pairs = rank_counts.iter().filter(|&&count| count == 2).count();
+    let pairing = match rank_counts.iter().max().copied().unwrap_or_default() {
+        4 => Pairing::Quads,
+        3 if n_pairs > 0 => Pairing::FullHouse,
+        3 => Pairing::Trips,
+        2 if n_pairs > 1 => Pairing::TwoPaired,
+        2 => Pairing::Paired,
+        _ => Pairing::Unpaired,
+    };
+
+    let max_suit_count = suit_counts.iter().max().copied().unwrap_or_default();
+    let suitedness = if max_suit_count as usize == board.len() {
+        Suitedness::Monotone
+    } else if max_suit_count == 1 {
+        Suitedness::Rainbow
+    } else {
+        Suitedness::TwoTone
+    };
+
+    let ranks: Vec<u8> = (0..13u8)
+        .rev()
+        .filter(|&rank| rank_counts[rank as usize] > 0)
+        .collect();
+    let gaps = ranks.windows(2).map(|pair| pair[0] - pair[1] - 1).collect();
+    let high_card = match ranks[0] {
+        12 => HighCardClass::Ace,
+        8.. => HighCardClass::Broadway,
+        5.. => HighCardClass::Middle,
+        _ => HighCardClass::Low,
+    };
+
+    // Two hole cards complete any five ranks in a row holding three board ranks
+    let rank_set = ranks
+        .iter()
+        .fold(0u32, |rank_set, rank| rank_set | 1 << rank);
+    let wheel: u32 = 1 << 12 | 0b1111 << variant.lowest_card_rank();
+    let straight_possible = (wheel & rank_set).count_ones() >= 3
+        || (0..9).any(|lowest| (0b11111 << lowest & rank_set).count_ones() >= 3);
+    let flush_possible = max_suit_count >= 3;
+
+    let (nut_hand, n_nut_combos) = nuts(board, variant);
+    Some(BoardTexture {
+        pairing,
+        suitedness,
+        max_suit_count,
+        gaps,
+        high_card,
+        straight_possible,
+        flush_possible,
+        nut_hand,
+        n_nut_combos,
+    })
+}
+
+/// Best hand of every two unseen cards, and how many make it
+fn nuts(board: &[Card], variant: GameVariant) -> (Rank, usize) {
+    let unseen: Vec<Card> = (variant.lowest_card_rank() * 4..52)
+        .map(Card)
+        .filter(|card| board.iter().all(|board_card| board_card.0 != card.0))
+        .collect();
+    let mut nut_hand = Rank::HighCard(0);
+    let mut n_nut_combos = 0;
+    for (i, first) in unseen.iter().enumerate() {
+        for second in unseen[i + 1..].iter() {
+            let hole_cards = [first.clone(), second.clone()];
+            let Some(made_hand) = best_hand(&hole_cards, board, variant) else {
+                continue;
+            };
+            let key = (category(&made_hand.rank, variant), made_hand.rank);
+            let nut_key = (category(&nut_hand, variant), nut_hand);
+            if key > nut_key {
+                nut_hand = made_hand.rank;
+                n_nut_combos = 1;
+            } else if key == nut_key {
+                n_nut_combos += 1;
+            }
+        }
+    }
+    (nut_hand, n_nut_combos)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::table::components::parse_cards;
+
+    fn holdem_texture(board: &str) -> BoardTexture {
+        board_texture(&parse_cards(board).unwrap(), GameVariant::Holdem).unwrap()
+    }
+
+    #[test]
+    fn monotone() {
+        let texture = holdem_texture("Ah7h2h");
+        assert_eq!(texture.suitedness, Suitedness::Monotone);
+        assert_eq!(texture.max_suit_count, 3);
+        assert_eq!(texture.high_card, HighCardClass::Ace);
+        assert!(texture.flush_possible);
+        assert!(!texture.straight_possible);
+        // Only the king and queen of hearts make the nut flush
+        assert!(matches!(texture.nut_hand, Rank::Flush(_)));
+        assert_eq!(texture.n_nut_combos, 1);
+    }
+
+    #[test]
+    fn paired() {
+        let texture = holdem_texture("KsKd7c");
+        assert_eq!(texture.pairing, Pairing::Paired);
+        assert_eq!(texture.suitedness, Suitedness::Rainbow);
+        assert_eq!(texture.gaps, vec![5]);
+        assert_eq!(texture.high_card, HighCardClass::Broadway);
+        assert!(!texture.straight_possible);
+        assert!(matches!(texture.nut_hand, Rank::FourOfAKind(_)));
+        assert_eq!(texture.n_nut_combos, 1);
+        let texture = holdem_texture("KsKd7c7s");
+        assert_eq!(texture.pairing, Pairing::TwoPaired);
+        assert_eq!(texture.suitedness, Suitedness::TwoTone);
+    }
+
+    #[test]
+    fn connected() {
+        let texture = holdem_texture("9h8c7d");
+        assert_eq!(texture.pairing, Pairing::Unpaired);
+        assert_eq!(texture.gaps, vec![0, 0]);
+        assert_eq!(texture.high_card, HighCardClass::Middle);
+        assert!(texture.straight_possible);
+        assert!(!texture.flush_possible);
+        // Any jack and ten
+        assert!(matches!(texture.nut_hand, Rank::Straight(_)));
+        assert_eq!(texture.n_nut_combos, 16);
+    }
+
+    #[test]
+    fn not_a_board() {
+        let cards = parse_cards("9h8c").unwrap();
+        assert!(board_texture(&cards, GameVariant::Holdem).is_none());
+    }
+}
@@ -0,0 +1,171 @@
+use bevy::utils::hashbrown::HashMap;
+use rand::{seq::SliceRandom, Rng};
+use rand_chacha::ChaCha8Rng;
+
+use super::{
+    actions::Action,
+    compare_hands::compare_hands,
+    components::{Card, Chips},
+    equity::{range_equity, EquityConfig},
+    range::{Combo, Range},
+    strategy::{GameView, Strategy},
+};
+
+/// Plays [[random_action]]
+pub struct RandomStrategy;
+
+impl Strategy for RandomStrategy {
+    fn decide(&mut self, view: &GameView, rng: &mut ChaCha8Rng) -> Action {
+        random_action(view, rng)
+    }
+}
+
+/// Random opponent: mostly passive, raises a pot preflop and half a pot postflop
+pub fn random_action<R: Rng>(view: &GameView, rng: &mut R) -> Action {
+    let betting = view.betting();
+    let legal_actions = betting.legal_actions();
+    let mut options: Vec<Action> = legal_actions.options();
+    if let Some(raise) = &legal_actions.raise {
+        // Raise a pot preflop, half a pot postflop, or the fixed-limit bet
+        let fraction = if view.street() == 0 { 1.0 } else { 0.5 };
+        let raise_to = betting
+            .pot_raise_to(fraction)
+            .clamp(raise.min_to.0, raise.max_to.0);
+        *options.last_mut().unwrap() = legal_actions.raise_action(Chips(raise_to));
+    }
+    let weights = if view.street() == 0 {
+        vec![0.7, 0.2, 0.1]
+    } else if options.len() == 2 {
+        vec![0.5, 0.5]
+    } else {
+        vec![0.6, 0.3, 0.1]
+    };
+    let weights_map = options
+        .iter()
+        .zip(weights.iter())
+        .map(|(action, w)| (*action, *w))
+        .collect::<HashMap<Action, f32>>();
+    *options
+        .choose_weighted(rng, |action| weights_map[action])
+        .unwrap()
+}
+
+/// Plays by pot odds: calls when its equity is worth the price, raises for value with a
+/// strong share of the pot, and sometimes bluffs
+#[derive(Debug, Clone)]
+pub struct EquityStrategy {
+    /// Hands the opponents are thought to hold, any hand if [[None]]. Only in hold'em and
+    /// short-deck.
+    pub opponent_range: Option<Range>,
+    /// Run-outs sampled per decision
+    pub n_samples: u32,
+    /// Probability of betting or raising a hand that is not worth it
+    pub bluff_frequency: f32,
+    /// Raises for value with this many times a fair share of the pot, one over the number of
+    /// players still in
+    pub value_raise_share: f32,
+    /// Bets and raises this fraction of the pot after calling
+    pub raise_pot_fraction: f32,
+}
+
+impl Default for EquityStrategy {
+    fn default() -> Self {
+        Self {
+            opponent_range: None,
+            n_samples: 1000,
+            bluff_frequency: 0.1,
+            value_raise_share: 1.3,
+            raise_pot_fraction: 0.75,
+        }
+    }
+}
+
+impl Strategy for EquityStrategy {
+    fn decide(&mut self, view: &GameView, rng: &mut ChaCha8Rng) -> Action {
+        let betting = view.betting();
+        let legal_actions = betting.legal_actions();
+        let raise = legal_actions.raise.map(|raise| {
+            let raise_to = betting
+                .pot_raise_to(self.raise_pot_fraction)
+                .clamp(raise.min_to.0, raise.max_to.0);
+            legal_actions.raise_action(Chips(raise_to))
+        });
+
+        let n_players = view.seats().iter().filter(|seat| seat.is_active).count();
+        let equity = self.equity(view, n_players - 1, rng);
+        let fair_share = 1.0 / n_players as f32;
+        let to_call = view.to_call().0;
+        let pot_odds = to_call as f32 / (view.pot().0 + to_call) as f32;
+        let bluffs = rng.gen::<f32>() < self.bluff_frequency;
+
+        match raise {
+            Some(raise) if equity >= fair_share * self.value_raise_share || bluffs => raise,
+            _ if to_call == 0 => Action::Check,
+            // Call, all-in if short, when the pot pays for it
+            _ if equity >= pot_odds => legal_actions.options()[1],
+            _ => Action::Fold,
+        }
+    }
+}
+
+impl EquityStrategy {
+    /// Share of the pot won against `n_opponents`, from 0 to 1
+    fn equity(&self, view: &GameView, n_opponents: usize, rng: &mut ChaCha8Rng) -> f32 {
+        let variant = view.config().variant;
+        if let (Some(range), [first, second]) = (&self.opponent_range, view.hole_cards()) {
+            let config = EquityConfig {
+                variant,
+                max_exact_boards: self.n_samples as u64,
+                n_samples: self.n_samples as u64,
+                seed: Some(rng.gen()),
+            };
+            let own_range = Combo::new(first, second).map(Range::from_combo);
+            let ranges: Option<Vec<Range>> = own_range
+                .map(|own_range| [vec![own_range], vec![range.clone(); n_opponents]].concat());
+            // Falls back on any hands when the ranges do not fit the cards seen
+            if let Some(Ok(result)) =
+                ranges.map(|ranges| range_equity(&config, &ranges, view.board(), &[]))
+            {
+                return result.players[0].equity as f32 / 100.0;
+            }
+        }
+        random_hands_equity(view, n_opponents, self.n_samples, rng)
+    }
+}
+
+/// Equity against `n_opponents` holding any hands, by sampling `n_samples` deals
+fn random_hands_equity(
+    view: &GameView,
+    n_opponents: usize,
+    n_samples: u32,
+    rng: &mut ChaCha8Rng,
+) -> f32 {
+    let variant = view.config().variant;
+    let hole_cards = view.hole_cards();
+    let board = view.board();
+    let mut deck: Vec<Card> = (variant.lowest_card_rank() * 4..52)
+        .map(Card)
+        .filter(|card| {
+            hole_cards
+                .iter()
+                .chain(board.iter())
+                .all(|seen| seen.0 != card.0)
+        })
+        .collect();
+    let n_hole_cards = variant.n_hole_cards();
+    let n_missing = 5 - board.len();
+    let mut won = 0.0;
+    for _ in 0..n_samples {
+        let (dealt, _) = deck.partial_shuffle(rng, n_opponents * n_hole_cards + n_missing);
+        let (opponent_cards, run_out) = dealt.split_at(n_opponents * n_hole_cards);
+        let full_board = [board, run_out].concat();
+        let hands: Vec<&[Card]> = std::iter::once(hole_cards)
+            .chain(opponent_cards.chunks(n_hole_cards))
+            .collect();
+        let winners = compare_hands(&hands, &full_board, variant);
+        if winners.contains(&0) {
+            won += 1.0 / winners.len() as f32;
+        }
+    }
+    won / n_samples as f32
+}
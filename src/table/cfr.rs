@@ -0,0 +1,615 @@
+use rand::{seq::SliceRandom, Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use super::{
+    components::Card,
+    lookup_evaluation::LookupEvaluator,
+    range::{class_combos, Combo, N_HAND_CLASSES},
+};
+
+/// Point in the betting tree of a [[BettingGame]]
+#[derive(Debug, Clone)]
+pub enum Node {
+    /// `player` chooses one of `actions`, each named and leading to the node of that index
+    Decision {
+        player: usize,
+        actions: Vec<(String, usize)>,
+    },
+    /// `folder` gave up, each player having put in `contributions`
+    Fold {
+        folder: usize,
+        contributions: [f64; 2],
+    },
+    Showdown {
+        contributions: [f64; 2],
+    },
+}
+
+/// Abstract heads-up game: chance deals each player a private bucket, then both bet along a
+/// public tree. Payoffs are in big blinds.
+#[derive(Debug, Clone)]
+pub struct BettingGame {
+    pub n_buckets: usize,
+    /// Probability of each pair of buckets, by bucket of the first player then of the second
+    pub deal_weights: Vec<Vec<f64>>,
+    /// Share of the pot the first player wins at showdown, indexed like
+    /// [[Self::deal_weights]]
+    pub equities: Vec<Vec<f64>>,
+    /// The root first
+    pub nodes: Vec<Node>,
+}
+
+/// Probability of every action, by node, then bucket of the player to act, then action.
+/// Empty at the nodes without a decision.
+#[derive(Debug, Clone)]
+pub struct StrategyTable {
+    pub probabilities: Vec<Vec<Vec<f64>>>,
+}
+
+impl StrategyTable {
+    /// Every action equally likely
+    pub fn uniform(game: &BettingGame) -> Self {
+        let probabilities = game
+            .nodes
+            .iter()
+            .map(|node| match node {
+                Node::Decision { actions, .. } => {
+                    vec![vec![1.0 / actions.len() as f64; actions.len()]; game.n_buckets]
+                }
+                _ => Vec::new(),
+            })
+            .collect();
+        Self { probabilities }
+    }
+
+    pub fn action_probabilities(&self, node: usize, bucket: usize) -> &[f64] {
+        &self.probabilities[node][bucket]
+    }
+}
+
+impl BettingGame {
+    /// Heads-up push or fold with `stack_bb` effective big blinds, one bucket per kind of
+    /// starting hand. The small blind, the first player, shoves or folds; the big blind calls
+    /// or folds.
+    pub fn push_fold(equities: &PreflopEquities, stack_bb: f64) -> Self {
+        let nodes = vec![
+            Node::Decision {
+                player: 0,
+                actions: vec![("fold".to_string(), 1), ("push".to_string(), 2)],
+            },
+            Node::Fold {
+                folder: 0,
+                contributions: [0.5, 1.0],
+            },
+            Node::Decision {
+                player: 1,
+                actions: vec![("fold".to_string(), 3), ("call".to_string(), 4)],
+            },
+            Node::Fold {
+                folder: 1,
+                contributions: [stack_bb, 1.0],
+            },
+            Node::Showdown {
+                contributions: [stack_bb, stack_bb],
+            },
+        ];
+        Self {
+            n_buckets: N_HAND_CLASSES,
+            deal_weights: equities.deal_weights.clone(),
+            equities: equities.equities.clone(),
+            nodes,
+        }
+    }
+
+    /// One heads-up fixed-limit betting round, then a showdown. The first player acts first
+    /// with `contributions` already in. Bets and raises are `bet_size`, at most `max_bets`
+    /// of them, a bigger blind counting as the first.
+    pub fn limit(
+        deal_weights: Vec<Vec<f64>>,
+        equities: Vec<Vec<f64>>,
+        contributions: [f64; 2],
+        bet_size: f64,
+        max_bets: u32,
+    ) -> Self {
+        let mut game = Self {
+            n_buckets: deal_weights.len(),
+            deal_weights,
+            equities,
+            nodes: Vec::new(),
+        };
+        let n_bets = (contributions[0] != contributions[1]) as u32;
+        let round = LimitRound { bet_size, max_bets };
+        round.add_node(&mut game.nodes, contributions, 0, n_bets, false);
+        game
+    }
+
+    /// Expected payoff of the first player when both play `table`
+    pub fn value(&self, table: &StrategyTable) -> f64 {
+        let reach = vec![1.0; self.n_buckets];
+        let values = self.evaluate(0, 0, &reach, &mut |node, _| {
+            Some(table.probabilities[node].clone())
+        });
+        values.iter().sum()
+    }
+
+    /// How much a best response wins against `table`, averaged over both seats, in big
+    /// blinds per hand. Zero at an equilibrium.
+    pub fn exploitability(&self, table: &StrategyTable) -> f64 {
+        let reach = vec![1.0; self.n_buckets];
+        let best_response_total: f64 = (0..2)
+            .map(|player| {
+                let values = self.evaluate(0, player, &reach, &mut |node, acting| {
+                    (acting != player).then(|| table.probabilities[node].clone())
+                });
+                values.iter().sum::<f64>()
+            })
+            .sum();
+        best_response_total / 2.0
+    }
+
+    /// Counterfactual values of `player`'s buckets below `node`, against the other player
+    /// reaching it with `opponent_reach`. `strategy` gives the probabilities at a node, or
+    /// [[None]] for `player` to take the best action.
+    fn evaluate(
+        &self,
+        node: usize,
+        player: usize,
+        opponent_reach: &[f64],
+        strategy: &mut impl FnMut(usize, usize) -> Option<Vec<Vec<f64>>>,
+    ) -> Vec<f64> {
+        match &self.nodes[node] {
+            Node::Decision {
+                player: acting,
+                actions,
+            } => {
+                let probabilities = strategy(node, *acting);
+                if *acting == player {
+                    let child_values: Vec<Vec<f64>> = actions
+                        .iter()
+                        .map(|(_, child)| self.evaluate(*child, player, opponent_reach, strategy))
+                        .collect();
+                    (0..self.n_buckets)
+                        .map(|bucket| {
+                            let action_values = child_values.iter().map(|values| values[bucket]);
+                            match &probabilities {
+                                Some(probabilities) => action_values
+                                    .zip(probabilities[bucket].iter())
+                                    .map(|(value, probability)| value * probability)
+                                    .sum(),
+                                None => action_values.fold(f64::MIN, f64::max),
+                            }
+                        })
+                        .collect()
+                } else {
+                    let probabilities = probabilities.expect("Opponent strategy at every node");
+                    let mut values = vec![0.0; self.n_buckets];
+                    for (action, (_, child)) in actions.iter().enumerate() {
+                        let child_reach: Vec<f64> = opponent_reach
+                            .iter()
+                            .zip(probabilities.iter())
+                            .map(|(reach, probabilities)| reach * probabilities[action])
+                            .collect();
+                        let child_values = self.evaluate(*child, player, &child_reach, strategy);
+                        for (value, child_value) in values.iter_mut().zip(child_values) {
+                            *value += child_value;
+                        }
+                    }
+                    values
+                }
+            }
+            terminal => (0..self.n_buckets)
+                .map(|bucket| {
+                    (0..self.n_buckets)
+                        .filter(|&opponent_bucket| opponent_reach[opponent_bucket] > 0.0)
+                        .map(|opponent_bucket| {
+                            let (first, second) = if player == 0 {
+                                (bucket, opponent_bucket)
+                            } else {
+                                (opponent_bucket, bucket)
+                            };
+                            let payoff = self.first_player_payoff(terminal, first, second);
+                            let payoff = if player == 0 { payoff } else { -payoff };
+                            self.deal_weights[first][second]
+                                * opponent_reach[opponent_bucket]
+                                * payoff
+                        })
+                        .sum()
+                })
+                .collect(),
+        }
+    }
+
+    fn first_player_payoff(&self, terminal: &Node, first: usize, second: usize) -> f64 {
+        match terminal {
+            Node::Fold {
+                folder: 0,
+                contributions,
+            } => -contributions[0],
+            Node::Fold { contributions, .. } => contributions[1],
+            Node::Showdown { contributions } => {
+                self.equities[first][second] * (contributions[0] + contributions[1])
+                    - contributions[0]
+            }
+            Node::Decision { .. } => unreachable!(),
+        }
+    }
+}
+
+/// Builds the tree of [[BettingGame::limit]]
+struct LimitRound {
+    bet_size: f64,
+    max_bets: u32,
+}
+
+impl LimitRound {
+    /// Index of the node where `actor` is to act. The round closes on a call or a check once
+    /// the other player has acted.
+    fn add_node(
+        &self,
+        nodes: &mut Vec<Node>,
+        contributions: [f64; 2],
+        actor: usize,
+        n_bets: u32,
+        other_has_acted: bool,
+    ) -> usize {
+        let index = nodes.len();
+        nodes.push(Node::Showdown { contributions });
+        let other = 1 - actor;
+        let mut actions = Vec::new();
+        let mut raised = contributions;
+        raised[actor] = contributions[other] + self.bet_size;
+        if contributions[other] > contributions[actor] {
+            nodes.push(Node::Fold {
+                folder: actor,
+                contributions,
+            });
+            actions.push(("fold".to_string(), nodes.len() - 1));
+            let mut called = contributions;
+            called[actor] = contributions[other];
+            let call = if other_has_acted {
+                nodes.push(Node::Showdown {
+                    contributions: called,
+                });
+                nodes.len() - 1
+            } else {
+                self.add_node(nodes, called, other, n_bets, true)
+            };
+            actions.push(("call".to_string(), call));
+            if n_bets < self.max_bets {
+                let raise = self.add_node(nodes, raised, other, n_bets + 1, true);
+                actions.push(("raise".to_string(), raise));
+            }
+        } else {
+            let check = if other_has_acted {
+                nodes.push(Node::Showdown { contributions });
+                nodes.len() - 1
+            } else {
+                self.add_node(nodes, contributions, other, n_bets, true)
+            };
+            actions.push(("check".to_string(), check));
+            if n_bets < self.max_bets {
+                let bet = self.add_node(nodes, raised, other, n_bets + 1, true);
+                actions.push(("bet".to_string(), bet));
+            }
+        }
+        nodes[index] = Node::Decision {
+            player: actor,
+            actions,
+        };
+        index
+    }
+}
+
+/// CFR+: regret matching on clipped cumulative regrets, alternating updates and a linearly
+/// weighted average strategy, which converges to an equilibrium
+pub struct CfrSolver<'a> {
+    game: &'a BettingGame,
+    /// Indexed like [[StrategyTable::probabilities]]
+    regrets: Vec<Vec<Vec<f64>>>,
+    strategy_sums: Vec<Vec<Vec<f64>>>,
+    pub n_iterations: u32,
+}
+
+impl<'a> CfrSolver<'a> {
+    pub fn new(game: &'a BettingGame) -> Self {
+        let zeros = StrategyTable::uniform(game)
+            .probabilities
+            .into_iter()
+            .map(|node| {
+                node.into_iter()
+                    .map(|bucket| vec![0.0; bucket.len()])
+                    .collect()
+            })
+            .collect::<Vec<Vec<Vec<f64>>>>();
+        Self {
+            game,
+            regrets: zeros.clone(),
+            strategy_sums: zeros,
+            n_iterations: 0,
+        }
+    }
+
+    pub fn run(&mut self, n_iterations: u32) {
+        let reach = vec![1.0; self.game.n_buckets];
+        for _ in 0..n_iterations {
+            self.n_iterations += 1;
+            for player in 0..2 {
+                self.update(0, player, &reach, &reach);
+            }
+        }
+    }
+
+    /// Average strategy of the iterations so far: the approximate equilibrium
+    pub fn average_strategy(&self) -> StrategyTable {
+        let probabilities = self
+            .strategy_sums
+            .iter()
+            .map(|node| node.iter().map(|sums| normalized(sums)).collect())
+            .collect();
+        StrategyTable { probabilities }
+    }
+
+    /// Regret matching: actions in proportion to their positive regrets
+    fn current_strategy(&self, node: usize) -> Vec<Vec<f64>> {
+        self.regrets[node]
+            .iter()
+            .map(|regrets| normalized(regrets))
+            .collect()
+    }
+
+    /// Counterfactual values of `player` below `node`, updating the regrets and the average
+    /// strategy of `player`
+    fn update(
+        &mut self,
+        node: usize,
+        player: usize,
+        own_reach: &[f64],
+        opponent_reach: &[f64],
+    ) -> Vec<f64> {
+        let game = self.game;
+        let Node::Decision {
+            player: acting,
+            actions,
+        } = &game.nodes[node]
+        else {
+            return game.evaluate(node, player, opponent_reach, &mut |_, _| None);
+        };
+        let strategy = self.current_strategy(node);
+        let mut values = vec![0.0; game.n_buckets];
+        if *acting == player {
+            let mut child_values = Vec::new();
+            for (action, (_, child)) in actions.iter().enumerate() {
+                let child_reach: Vec<f64> = own_reach
+                    .iter()
+                    .zip(strategy.iter())
+                    .map(|(reach, probabilities)| reach * probabilities[action])
+                    .collect();
+                child_values.push(self.update(*child, player, &child_reach, opponent_reach));
+            }
+            let weight = self.n_iterations as f64;
+            for bucket in 0..game.n_buckets {
+                values[bucket] = (0..actions.len())
+                    .map(|action| strategy[bucket][action] * child_values[action][bucket])
+                    .sum();
+                for action in 0..actions.len() {
+                    let regret = &mut self.regrets[node][bucket][action];
+                    *regret = (*regret + child_values[action][bucket] - values[bucket]).max(0.0);
+                    self.strategy_sums[node][bucket][action] +=
+                        weight * own_reach[bucket] * strategy[bucket][action];
+                }
+            }
+        } else {
+            for (action, (_, child)) in actions.iter().enumerate() {
+                let child_reach: Vec<f64> = opponent_reach
+                    .iter()
+                    .zip(strategy.iter())
+                    .map(|(reach, probabilities)| reach * probabilities[action])
+                    .collect();
+                let child_values = self.update(*child, player, own_reach, &child_reach);
+                for (value, child_value) in values.iter_mut().zip(child_values) {
+                    *value += child_value;
+                }
+            }
+        }
+        values
+    }
+}
+
+/// `weights` scaled to sum to 1, uniform if none is positive
+pub(super) fn normalized(weights: &[f64]) -> Vec<f64> {
+    let total: f64 = weights.iter().map(|weight| weight.max(0.0)).sum();
+    if total > 0.0 {
+        weights
+            .iter()
+            .map(|weight| weight.max(0.0) / total)
+            .collect()
+    } else {
+        vec![1.0 / weights.len() as f64; weights.len()]
+    }
+}
+
+/// Hold'em all-in equity of each kind of starting hand against each other, indexed by
+/// [[Combo::class_index]]
+#[derive(Debug, Clone)]
+pub struct PreflopEquities {
+    /// Probability of dealing a hand of each kind to both players, without a card twice
+    pub deal_weights: Vec<Vec<f64>>,
+    /// Share of the pot of the first hand, from sampled deals
+    pub equities: Vec<Vec<f64>>,
+}
+
+/// Kinds of starting hands grouped by strength
+#[derive(Debug, Clone)]
+pub struct HandBuckets {
+    /// By [[Combo::class_index]], the strongest hands in the last bucket
+    pub class_buckets: Vec<usize>,
+    /// See [[BettingGame::deal_weights]]
+    pub deal_weights: Vec<Vec<f64>>,
+    /// See [[BettingGame::equities]]
+    pub equities: Vec<Vec<f64>>,
+}
+
+impl PreflopEquities {
+    /// Deals `n_deals` random combos and boards for every two kinds of starting hands
+    pub fn estimate(n_deals: u32, seed: u64) -> Self {
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        let evaluator = LookupEvaluator::holdem();
+        let class_combos: Vec<Vec<Combo>> = (0..N_HAND_CLASSES).map(class_combos).collect();
+
+        let mut deal_weights = vec![vec![0.0; N_HAND_CLASSES]; N_HAND_CLASSES];
+        let combos: Vec<Combo> = Combo::all().collect();
+        let mut n_deals_total = 0.0;
+        for first in combos.iter() {
+            for second in combos
+                .iter()
+                .filter(|combo| combo.mask() & first.mask() == 0)
+            {
+                deal_weights[first.class_index()][second.class_index()] += 1.0;
+                n_deals_total += 1.0;
+            }
+        }
+        for weight in deal_weights.iter_mut().flatten() {
+            *weight /= n_deals_total;
+        }
+
+        let mut equities = vec![vec![0.5; N_HAND_CLASSES]; N_HAND_CLASSES];
+        for first in 0..N_HAND_CLASSES {
+            for second in first + 1..N_HAND_CLASSES {
+                let mut won = 0.0;
+                for _ in 0..n_deals {
+                    let (a, b) = loop {
+                        let a = *class_combos[first].choose(&mut rng).unwrap();
+                        let b = *class_combos[second].choose(&mut rng).unwrap();
+                        if a.mask() & b.mask() == 0 {
+                            break (a, b);
+                        }
+                    };
+                    let dead = a.mask() | b.mask();
+                    let mut board: Vec<Card> = Vec::with_capacity(5);
+                    while board.len() < 5 {
+                        let card: u8 = rng.gen_range(0..52);
+                        if dead & 1 << card == 0 && board.iter().all(|dealt| dealt.0 != card) {
+                            board.push(Card(card));
+                        }
+                    }
+                    let rank_a = evaluator.rank(board.iter().chain(a.cards().iter()));
+                    let rank_b = evaluator.rank(board.iter().chain(b.cards().iter()));
+                    won += match rank_a.cmp(&rank_b) {
+                        std::cmp::Ordering::Greater => 1.0,
+                        std::cmp::Ordering::Equal => 0.5,
+                        std::cmp::Ordering::Less => 0.0,
+                    };
+                }
+                equities[first][second] = won / n_deals as f64;
+                equities[second][first] = 1.0 - equities[first][second];
+            }
+        }
+        Self {
+            deal_weights,
+            equities,
+        }
+    }
+
+    /// `n_buckets` groups of about as many combos, by equity against any hand
+    pub fn bucketed(&self, n_buckets: usize) -> HandBuckets {
+        let class_weight = |class: usize| self.deal_weights[class].iter().sum::<f64>();
+        let strength = |class: usize| {
+            let equity: f64 = (0..N_HAND_CLASSES)
+                .map(|other| self.deal_weights[class][other] * self.equities[class][other])
+                .sum();
+            equity / class_weight(class)
+        };
+        let mut classes: Vec<usize> = (0..N_HAND_CLASSES).collect();
+        classes.sort_by(|a, b| strength(*a).total_cmp(&strength(*b)));
+
+        let mut class_buckets = vec![0; N_HAND_CLASSES];
+        let mut cumulative_weight = 0.0;
+        for class in classes {
+            let middle = cumulative_weight + class_weight(class) / 2.0;
+            class_buckets[class] = ((middle * n_buckets as f64) as usize).min(n_buckets - 1);
+            cumulative_weight += class_weight(class);
+        }
+
+        let mut deal_weights = vec![vec![0.0; n_buckets]; n_buckets];
+        let mut won = vec![vec![0.0; n_buckets]; n_buckets];
+        for first in 0..N_HAND_CLASSES {
+            for second in 0..N_HAND_CLASSES {
+                let weight = self.deal_weights[first][second];
+                let (a, b) = (class_buckets[first], class_buckets[second]);
+                deal_weights[a][b] += weight;
+                won[a][b] += weight * self.equities[first][second];
+            }
+        }
+        let equities = won
+            .iter()
+            .zip(deal_weights.iter())
+            .map(|(won, weights)| {
+                won.iter()
+                    .zip(weights.iter())
+                    .map(|(won, weight)| if *weight > 0.0 { won / weight } else { 0.5 })
+                    .collect()
+            })
+            .collect();
+        HandBuckets {
+            class_buckets,
+            deal_weights,
+            equities,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Kuhn poker: one card each out of three, an ante of one, one bet of one
+    fn kuhn_poker() -> BettingGame {
+        let deal_weights = (0..3)
+            .map(|a| {
+                (0..3)
+                    .map(|b| if a == b { 0.0 } else { 1.0 / 6.0 })
+                    .collect()
+            })
+            .collect();
+        let equities = (0..3)
+            .map(|a| (0..3).map(|b| if a > b { 1.0 } else { 0.0 }).collect())
+            .collect();
+        BettingGame::limit(deal_weights, equities, [1.0, 1.0], 1.0, 1)
+    }
+
+    #[test]
+    fn kuhn_poker_equilibrium() {
+        let game = kuhn_poker();
+        let uniform = StrategyTable::uniform(&game);
+        assert!(game.exploitability(&uniform) > 0.1);
+
+        let mut solver = CfrSolver::new(&game);
+        solver.run(2000);
+        let table = solver.average_strategy();
+        // The first player loses 1/18 per hand
+        assert!((game.value(&table) + 1.0 / 18.0).abs() < 1e-3);
+        assert!(game.exploitability(&table) < 1e-3);
+    }
+
+    #[test]
+    fn push_fold_converges() {
+        let equities = PreflopEquities::estimate(10, 1);
+        let game = BettingGame::push_fold(&equities, 10.0);
+        assert!(game.exploitability(&StrategyTable::uniform(&game)) > 0.1);
+
+        let mut solver = CfrSolver::new(&game);
+        solver.run(200);
+        assert!(game.exploitability(&solver.average_strategy()) < 1e-3);
+    }
+
+    #[test]
+    fn bucketed_limit_converges() {
+        let buckets = PreflopEquities::estimate(10, 1).bucketed(6);
+        let game = BettingGame::limit(buckets.deal_weights, buckets.equities, [0.5, 1.0], 1.0, 4);
+        assert!(game.exploitability(&StrategyTable::uniform(&game)) > 0.1);
+
+        let mut solver = CfrSolver::new(&game);
+        solver.run(500);
+        assert!(game.exploitability(&solver.average_strategy()) < 1e-3);
+    }
+}
@@ -1,20 +1,71 @@
-use super::hand_evaluation::{self as he, Rankable};
+use super::hand_evaluation as he;
+use super::lookup_evaluation::LookupEvaluator;
 
 use super::components::Card;
+use super::resources::GameVariant;
 
-pub fn compare_hands(hands: &Vec<Vec<Card>>) -> Vec<usize> {
-    if hands.len() == 0 {
+/// Best five-card rank of a player at showdown.
+/// In Omaha the hand is made of exactly two hole cards and three board cards.
+/// Short-deck ranks must be compared as [[he::ShortDeckRank]].
+/// [[None]] in Omaha before the flop, when no two hole cards and three board cards exist.
+pub fn rank_hand(hole_cards: &[Card], board: &[Card], variant: GameVariant) -> Option<he::Rank> {
+    match variant {
+        GameVariant::ShortDeck => {
+            Some(LookupEvaluator::short_deck().rank(hole_cards.iter().chain(board.iter())))
+        }
+        GameVariant::Holdem => {
+            Some(LookupEvaluator::holdem().rank(hole_cards.iter().chain(board.iter())))
+        }
+        GameVariant::Omaha => pairs(hole_cards.len())
+            .flat_map(|(i, j)| {
+                triples(board.len()).map(move |(k, l, m)| {
+                    LookupEvaluator::holdem().rank([
+                        &hole_cards[i],
+                        &hole_cards[j],
+                        &board[k],
+                        &board[l],
+                        &board[m],
+                    ])
+                })
+            })
+            .max(),
+    }
+}
+
+fn pairs(n: usize) -> impl Iterator<Item = (usize, usize)> {
+    (0..n).flat_map(move |i| (i + 1..n).map(move |j| (i, j)))
+}
+
+fn triples(n: usize) -> impl Iterator<Item = (usize, usize, usize)> {
+    pairs(n).flat_map(move |(i, j)| (j + 1..n).map(move |k| (i, j, k)))
+}
+
+/// Indices of the winning hands, several on a split pot. None when the hands cannot be
+/// ranked, see [[rank_hand]].
+pub fn compare_hands(hole_cards: &[&[Card]], board: &[Card], variant: GameVariant) -> Vec<usize> {
+    if hole_cards.is_empty() {
         return vec![];
     }
-    if hands.len() == 1 {
+    if hole_cards.len() == 1 {
         return vec![0];
     }
     let mut ranks: Vec<he::Rank> = Vec::new();
-    for hand in hands {
-        let hand: Vec<he::Card> = hand.iter().map(|x| he::Card::new(x.0)).collect();
-        let rank = hand.rank();
+    for hand in hole_cards {
+        let Some(rank) = rank_hand(hand, board, variant) else {
+            return vec![];
+        };
         ranks.push(rank);
     }
+    match variant {
+        GameVariant::ShortDeck => {
+            let ranks: Vec<he::ShortDeckRank> = ranks.into_iter().map(he::ShortDeckRank).collect();
+            best_indices(&ranks)
+        }
+        GameVariant::Holdem | GameVariant::Omaha => best_indices(&ranks),
+    }
+}
+
+fn best_indices<T: Ord>(ranks: &[T]) -> Vec<usize> {
     let max_rank = ranks.iter().max().unwrap();
     let mut indices = Vec::new();
     for (i, rank) in ranks.iter().enumerate() {
@@ -1,5 +1,9 @@
+use std::{fmt, str::FromStr};
+
 use bevy::prelude::*;
 
+use super::resources::TableConfig;
+
 /// [[Copy]] because immutable
 #[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct SeatIndex(pub u8);
@@ -33,10 +37,10 @@ where
         &mut chips.0
     }
 
-    fn amount_str(&self) -> String {
+    fn amount_str(&self, big_blind: Chips) -> String {
         let amount = self.amount();
         if amount > 0 {
-            format!("{:.1} bb", self.amount() as f32 / 2.0)
+            format!("{:.1} bb", amount as f32 / big_blind.0 as f32)
         } else {
             "".to_string()
         }
@@ -69,10 +73,22 @@ pub struct PooledPot(pub Chips);
 
 impl Amount for PooledPot {}
 
-/// 0 to 5 on a 6-max table: number of opponents to act after the player
+/// 0 to 5 on a 6-max table: number of opponents to act after the player.
+/// 0 is the big blind, 1 is the small blind, 2 is the button. Heads-up the small blind has the
+/// button.
 #[derive(Component, Debug)]
 pub struct PreflopPosition(pub u8);
 
+impl PreflopPosition {
+    pub fn is_button(&self, n_seats: u8) -> bool {
+        if n_seats == 2 {
+            self.0 == 1
+        } else {
+            self.0 == 2
+        }
+    }
+}
+
 #[derive(Component, Debug)]
 pub struct PlayerName(pub String);
 
@@ -85,6 +101,14 @@ pub struct PlayerIsActive;
 #[derive(Component, Debug)]
 pub struct PlayerInAction;
 
+/// Busted out of the tournament: no longer dealt in
+#[derive(Component, Debug)]
+pub struct PlayerIsEliminated;
+
+/// Active player with no chips behind: stays in the handout but takes no more actions
+#[derive(Component, Debug)]
+pub struct PlayerIsAllIn;
+
 #[derive(Component, Debug)]
 pub struct PlayerIsHero;
 
@@ -142,14 +166,80 @@ const RANKS: [&str; 13] = [
     "2", "3", "4", "5", "6", "7", "8", "9", "T", "J", "Q", "K", "A",
 ];
 
+/// In the order of the deck: spades, clubs, diamonds, hearts
+const SUITS: [char; 4] = ['s', 'c', 'd', 'h'];
+
+/// "Ah", "Td", ...
+impl fmt::Display for Card {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_defined() {
+            write!(f, "{}{}", self.rank_str(), SUITS[self.suit() as usize])
+        } else {
+            write!(f, "X")
+        }
+    }
+}
+
+impl FromStr for Card {
+    type Err = ParseCardError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.chars();
+        let (Some(rank), Some(suit), None) = (chars.next(), chars.next(), chars.next()) else {
+            return Err(ParseCardError(s.to_string()));
+        };
+        let rank = RANKS
+            .iter()
+            .position(|r| r.starts_with(rank.to_ascii_uppercase()));
+        let suit = SUITS.iter().position(|&x| x == suit.to_ascii_lowercase());
+        match (rank, suit) {
+            (Some(rank), Some(suit)) => Ok(Card((rank * 4 + suit) as u8)),
+            _ => Err(ParseCardError(s.to_string())),
+        }
+    }
+}
+
+/// Cards written together or apart: "AhKd", "Ah Kd" or "Ah,Kd"
+pub fn parse_cards(text: &str) -> Result<Vec<Card>, ParseCardError> {
+    let chars: Vec<char> = text
+        .chars()
+        .filter(|c| !c.is_whitespace() && *c != ',')
+        .collect();
+    if !chars.len().is_multiple_of(2) {
+        return Err(ParseCardError(text.to_string()));
+    }
+    chars
+        .chunks(2)
+        .map(|card| card.iter().collect::<String>().parse())
+        .collect()
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseCardError(pub String);
+
+impl fmt::Display for ParseCardError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid card \"{}\", expected a rank and a suit like Ah or Td",
+            self.0
+        )
+    }
+}
+
 #[derive(Component, Debug)]
 pub struct HoleCardsFaceUp;
 
 #[derive(Component, Debug)]
 pub struct HoleCardsHidden;
 
+/// Description of the hand a player shows down, e.g. "Flush, Ace high"
+#[derive(Component, Debug)]
+pub struct ShownHand(pub String);
+
+/// Two cards in hold'em, four in Omaha
 #[derive(Component, Debug)]
-pub struct HoleCards(pub [Card; 2]);
+pub struct HoleCards(pub Vec<Card>);
 
 #[derive(Bundle, Debug)]
 pub struct PlayerBundle {
@@ -170,6 +260,32 @@ pub struct BoardCards(pub [Card; 5]);
 #[derive(Component, Debug)]
 pub struct Street(pub u8);
 
+/// Front level and size of the last full bet or raise on the street.
+/// An all-in for less than a full raise does not change it.
+#[derive(Debug, Clone, Copy)]
+pub struct LastFullRaise {
+    pub to: Chips,
+    pub increment: Chips,
+}
+
+impl LastFullRaise {
+    /// Blinds are the opening bet preflop. A straddle counts as a raise of its own size.
+    pub fn preflop(config: &TableConfig, n_players: u8) -> Self {
+        Self {
+            to: config.largest_blind(n_players),
+            increment: config.largest_blind(n_players),
+        }
+    }
+
+    /// Minimum bet is the big blind postflop
+    pub fn postflop(config: &TableConfig) -> Self {
+        Self {
+            to: Chips(0),
+            increment: config.big_blind,
+        }
+    }
+}
+
 #[derive(Bundle, Debug)]
 pub struct BoardBundle {
     pub board_cards: BoardCards,
@@ -1,25 +1,44 @@
-use rand::seq::SliceRandom;
+use rand::{seq::SliceRandom, Rng};
 use std::collections::VecDeque;
 
 use super::components::Card;
+use super::resources::GameVariant;
 
+#[derive(Debug, Clone)]
 pub struct Deck {
     cards: VecDeque<Card>,
 }
 
 impl Deck {
-    pub fn new_shuffled() -> Self {
+    /// 52 cards, 36 in short-deck
+    pub fn new_shuffled<R: Rng>(variant: GameVariant, rng: &mut R) -> Self {
+        let first_card = variant.lowest_card_rank() * 4;
         let mut cards = Vec::with_capacity(52);
-        for i in 0..52 {
-            cards.push(Card(i as u8));
+        for i in first_card..52 {
+            cards.push(Card(i));
         }
-        let rng = &mut rand::thread_rng();
         cards.shuffle(rng);
         Self {
             cards: cards.into(),
         }
     }
 
+    /// Draws the `preset` cards in order, a random card from the rest of the deck where there
+    /// is [[None]]
+    pub fn new_preset<R: Rng>(variant: GameVariant, rng: &mut R, preset: &[Option<Card>]) -> Self {
+        let is_preset = |card: &Card| preset.iter().flatten().any(|preset| preset.0 == card.0);
+        let mut rest = Self::new_shuffled(variant, rng)
+            .cards
+            .into_iter()
+            .filter(|card| !is_preset(card));
+        let mut cards: VecDeque<Card> = preset
+            .iter()
+            .map(|card| card.clone().unwrap_or_else(|| rest.next().unwrap()))
+            .collect();
+        cards.extend(rest);
+        Self { cards }
+    }
+
     pub fn draw(&mut self) -> Card {
         self.cards.pop_front().unwrap()
     }
@@ -0,0 +1,265 @@
+use super::{
+    compare_hands::compare_hands,
+    components::Card,
+    hand_evaluation::Rank,
+    made_hand::{best_hand, MadeHand},
+    resources::GameVariant,
+};
+
+/// What a player holds on the flop or the turn and what may come
+#[derive(Debug, Clone)]
+pub struct DrawAnalysis {
+    pub made_hand: MadeHand,
+    /// One more card of a suit makes a flush with a hole card
+    pub flush_draw: bool,
+    /// Two ranks make a straight with a hole card: open-ended, or a double gutshot
+    pub open_ended_straight_draw: bool,
+    /// Only one rank makes a straight with a hole card
+    pub gutshot: bool,
+    /// On the flop, a flush with both the turn and the river
+    pub backdoor_flush_draw: bool,
+    /// On the flop, a straight with both the turn and the river and no straight draw yet
+    pub backdoor_straight_draw: bool,
+    /// Hole cards above every board card
+    pub n_overcards: u8,
+    /// Next cards that give a better kind of hand, one the board alone does not give
+    pub outs: Vec<Card>,
+}
+
+/// Made hand, draws and outs of `hole_cards` on a flop or a turn `board`.
+/// [[None]] on other streets, and in Omaha.
+pub fn analyze_draws(
+    hole_cards: &[Card],
+    board: &[Card],
+    variant: GameVariant,
+) -> Option<DrawAnalysis> {
+    if !(3..=4).contains(&board.len()) || variant == GameVariant::Omaha {
+        return None;
+    }
+    let made_hand = best_hand(hole_cards, board, variant)?;
+    let cards: Vec<Card> = [hole_cards, board].concat();
+    let deck = unseen_cards(variant, &cards);
+    let wheel = wheel(variant);
+
+    let has_flush = has_flush(&cards);
+    let flush_draw = !has_flush && suit_counts_with_hole_card(hole_cards, board).contains(&4);
+    let backdoor_flush_draw = board.len() == 3
+        && !has_flush
+        && !flush_draw
+        && suit_counts_with_hole_card(hole_cards, board).contains(&3);
+
+    let board_rank_set = rank_set(board);
+    let rank_set = rank_set(&cards);
+    let makes_straight = |ranks: u32| {
+        has_straight(rank_set | ranks, wheel) && !has_straight(board_rank_set | ranks, wheel)
+    };
+    let has_straight = has_straight(rank_set, wheel);
+    let deck_ranks = variant.lowest_card_rank()..13;
+    let n_straight_ranks = deck_ranks
+        .clone()
+        .filter(|rank| !has_straight && makes_straight(1 << rank))
+        .count();
+    let open_ended_straight_draw = n_straight_ranks >= 2;
+    let gutshot = n_straight_ranks == 1;
+    let backdoor_straight_draw = board.len() == 3
+        && !has_straight
+        && n_straight_ranks == 0
+        && deck_ranks.clone().any(|turn| {
+            deck_ranks
+                .clone()
+                .any(|river| turn < river && makes_straight(1 << turn | 1 << river))
+        });
+
+    let top_board_rank = board.iter().map(Card::rank).max().unwrap_or_default();
+    let n_overcards = hole_cards
+        .iter()
+        .filter(|card| card.rank() > top_board_rank)
+        .count() as u8;
+
+    let category = category(&made_hand.rank, variant);
+    let outs = deck
+        .into_iter()
+        .filter(|card| {
+            let next_board = [board, std::slice::from_ref(card)].concat();
+            let next_category = best_hand(hole_cards, &next_board, variant)
+                .map(|next_hand| self::category(&next_hand.rank, variant))
+                .unwrap_or_default();
+            next_category > category && next_category > board_category(&next_board, variant)
+        })
+        .collect();
+
+    Some(DrawAnalysis {
+        made_hand,
+        flush_draw,
+        open_ended_straight_draw,
+        gutshot,
+        backdoor_flush_draw,
+        backdoor_straight_draw,
+        n_overcards,
+        outs,
+    })
+}
+
+/// Next cards after which `hole_cards` beat `opponent_hole_cards`, ties aside
+pub fn outs_against(
+    hole_cards: &[Card],
+    opponent_hole_cards: &[Card],
+    board: &[Card],
+    variant: GameVariant,
+) -> Vec<Card> {
+    let known_cards: Vec<Card> = [hole_cards, opponent_hole_cards, board].concat();
+    unseen_cards(variant, &known_cards)
+        .into_iter()
+        .filter(|card| {
+            let next_board = [board, std::slice::from_ref(card)].concat();
+            compare_hands(&[hole_cards, opponent_hole_cards], &next_board, variant) == [0]
+        })
+        .collect()
+}
+
+/// Kind of hand from 0 for a high card, a flush above a full house in short-deck
+pub(super) fn category(rank: &Rank, variant: GameVariant) -> u8 {
+    match (rank, variant) {
+        (Rank::HighCard(_), _) => 0,
+        (Rank::OnePair(_), _) => 1,
+        (Rank::TwoPair(_), _) => 2,
+        (Rank::ThreeOfAKind(_), _) => 3,
+        (Rank::Straight(_), _) => 4,
+        (Rank::Flush(_), GameVariant::ShortDeck) => 6,
+        (Rank::Flush(_), _) => 5,
+        (Rank::FullHouse(_), GameVariant::ShortDeck) => 5,
+        (Rank::FullHouse(_), _) => 6,
+        (Rank::FourOfAKind(_), _) => 7,
+        (Rank::StraightFlush(_), _) => 8,
+    }
+}
+
+/// [[category]] of the board cards alone, fewer than five counting as a high card or sets
+pub(super) fn board_category(board: &[Card], variant: GameVariant) -> u8 {
+    if board.len() == 5 {
+        return best_hand(&[], board, variant)
+            .map(|made_hand| category(&made_hand.rank, variant))
+            .unwrap_or_default();
+    }
+    let mut counts = [0u8; 13];
+    for card in board {
+        counts[card.rank() as usize] += 1;
+    }
+    let n_pairs = counts.iter().filter(|&&count| count == 2).count();
+    let max_count = counts.iter().max().copied().unwrap_or_default();
+    match (max_count, n_pairs) {
+        (4, _) => 7,
+        (3, 1) => category(&Rank::FullHouse(0), variant),
+        (3, _) => 3,
+        (2, 2) => 2,
+        (2, _) => 1,
+        _ => 0,
+    }
+}
+
+/// Cards of the `variant` deck not in `known_cards`
+fn unseen_cards(variant: GameVariant, known_cards: &[Card]) -> Vec<Card> {
+    (variant.lowest_card_rank() * 4..52)
+        .filter(|&card| known_cards.iter().all(|known| known.0 != card))
+        .map(Card)
+        .collect()
+}
+
+fn rank_set(cards: &[Card]) -> u32 {
+    cards
+        .iter()
+        .fold(0, |rank_set, card| rank_set | 1 << card.rank())
+}
+
+fn has_flush(cards: &[Card]) -> bool {
+    (0..4).any(|suit| cards.iter().filter(|card| card.suit() == suit).count() >= 5)
+}
+
+/// Number of cards in every suit held by a hole card
+fn suit_counts_with_hole_card(hole_cards: &[Card], board: &[Card]) -> Vec<usize> {
+    (0..4)
+        .filter(|&suit| hole_cards.iter().any(|card| card.suit() == suit))
+        .map(|suit| {
+            hole_cards
+                .iter()
+                .chain(board.iter())
+                .filter(|card| card.suit() == suit)
+                .count()
+        })
+        .collect()
+}
+
+/// Ace to five, or ace to nine in short-deck
+fn wheel(variant: GameVariant) -> u32 {
+    let ace = 1 << 12;
+    let lowest = variant.lowest_card_rank();
+    ace | (0b1111 << lowest)
+}
+
+fn has_straight(rank_set: u32, wheel: u32) -> bool {
+    let run = rank_set & rank_set << 1 & rank_set << 2 & rank_set << 3 & rank_set << 4;
+    run != 0 || rank_set & wheel == wheel
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::table::components::parse_cards;
+
+    fn holdem_draws(hole_cards: &str, board: &str) -> DrawAnalysis {
+        let hole_cards = parse_cards(hole_cards).unwrap();
+        let board = parse_cards(board).unwrap();
+        analyze_draws(&hole_cards, &board, GameVariant::Holdem).unwrap()
+    }
+
+    fn n_outs_of_rank(draws: &DrawAnalysis, rank: &str) -> usize {
+        let rank = parse_cards(&format!("{}c", rank)).unwrap()[0].rank();
+        draws.outs.iter().filter(|card| card.rank() == rank).count()
+    }
+
+    #[test]
+    fn open_ender() {
+        let draws = holdem_draws("9h8c", "7d6s2c");
+        assert!(draws.open_ended_straight_draw);
+        assert!(!draws.gutshot);
+        assert!(!draws.flush_draw);
+        // Eight cards make the straight, six more pair a hole card
+        assert_eq!(n_outs_of_rank(&draws, "T"), 4);
+        assert_eq!(n_outs_of_rank(&draws, "5"), 4);
+        assert_eq!(n_outs_of_rank(&draws, "9"), 3);
+        assert_eq!(draws.outs.len(), 14);
+    }
+
+    #[test]
+    fn gutshot() {
+        let draws = holdem_draws("9h8c", "Jd7s2c");
+        assert!(draws.gutshot);
+        assert!(!draws.open_ended_straight_draw);
+        assert_eq!(n_outs_of_rank(&draws, "T"), 4);
+        assert_eq!(draws.outs.len(), 10);
+    }
+
+    #[test]
+    fn combo_draw() {
+        let draws = holdem_draws("9h8h", "7h6h2c");
+        assert!(draws.flush_draw);
+        assert!(draws.open_ended_straight_draw);
+        assert!(!draws.backdoor_flush_draw);
+        // Nine hearts, six other tens and fives, six other nines and eights
+        assert_eq!(draws.outs.iter().filter(|card| card.suit() == 3).count(), 9);
+        assert_eq!(draws.outs.len(), 21);
+    }
+
+    #[test]
+    fn overcards_and_backdoors() {
+        let draws = holdem_draws("AhKh", "Qh7d2c");
+        assert_eq!(draws.n_overcards, 2);
+        assert!(draws.backdoor_flush_draw);
+        assert!(draws.backdoor_straight_draw);
+        assert!(!draws.gutshot);
+        // Running out the turn leaves no backdoor
+        let draws = holdem_draws("AhKh", "Qh7d2c3s");
+        assert!(!draws.backdoor_flush_draw);
+        assert!(!draws.backdoor_straight_draw);
+    }
+}
@@ -0,0 +1,580 @@
+use std::time::Duration;
+
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use super::{
+    actions::{Action, ActionError, LegalActions},
+    betting::Betting,
+    compare_hands::compare_hands,
+    components::{Card, Chips, LastFullRaise},
+    deck::Deck,
+    pots::{build_pots, settle_pots},
+    resources::{TableConfig, MAX_SEATS, MIN_SEATS},
+    scenario::{Scenario, ScenarioError},
+    tournament::{BlindLevel, TournamentState},
+};
+
+/// Where the handout stands, i.e. what the engine expects next
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Phase {
+    /// The player in action must act: [[GameState::apply_action]]
+    Betting,
+    /// Betting round is over, chips in front go to the pot
+    BettingRoundOver,
+    /// Next community cards to deal
+    Dealing,
+    /// Players still in show their cards
+    Showdown,
+    /// Pots go to the winners
+    Settling,
+    /// Next handout to start
+    HandoutEnded,
+    /// Sit-and-go is over: one player has all the chips
+    TournamentFinished,
+}
+
+/// What happened during a step, in order
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GameEvent {
+    /// Forced bets are posted and hole cards dealt. `seed` replays the handout.
+    HandoutStarted {
+        seed: u64,
+    },
+    PlayerActed {
+        seat_index: usize,
+        action: Action,
+    },
+    ChipsPooled,
+    /// Community cards of `street` are dealt: 1 for the flop, 2 for the turn, 3 for the river
+    CardsDealt {
+        street: u8,
+    },
+    CardsShown {
+        seat_index: usize,
+    },
+    PotWon {
+        seat_index: usize,
+        amount: Chips,
+    },
+    BlindsUp(BlindLevel),
+    PlayerEliminated {
+        seat_index: usize,
+        place: u8,
+    },
+    TournamentFinished {
+        winner_seat_index: usize,
+    },
+}
+
+/// An action of the current handout
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ActionRecord {
+    pub street: u8,
+    pub seat_index: usize,
+    pub action: Action,
+}
+
+/// A player's seat at the table
+#[derive(Debug, Clone)]
+pub struct Seat {
+    pub name: String,
+    /// Chips off the table, used to top the stack up in a cash game
+    pub bankroll: Chips,
+    pub stack: Chips,
+    /// Chips moved in front of the player on this street. Holds the winnings once the pots are
+    /// settled.
+    pub front: Chips,
+    /// Chips put into the pot over the whole handout
+    pub contribution: Chips,
+    /// Undefined cards when not dealt in
+    pub hole_cards: Vec<Card>,
+    /// See [[super::components::PreflopPosition]]
+    pub preflop_position: u8,
+    /// Dealt in and not folded
+    pub is_active: bool,
+    pub is_all_in: bool,
+    /// Has acted on the current street
+    pub has_acted: bool,
+    /// Busted out of the tournament: no longer dealt in
+    pub is_eliminated: bool,
+    /// Hole cards shown at showdown
+    pub shows_cards: bool,
+}
+
+/// Whole state of a table, free of any ECS or timing concern.
+///
+/// Betting is driven by [[GameState::apply_action]], every other phase by
+/// [[GameState::advance]]. Both return what happened as [[GameEvent]]s.
+#[derive(Debug, Clone)]
+pub struct GameState {
+    /// Stakes follow the tournament blind levels
+    pub config: TableConfig,
+    /// Indexed by seat index
+    pub seats: Vec<Seat>,
+    /// Undefined cards are not dealt yet
+    pub board: [Card; 5],
+    /// All chips in play, including those in front of the players
+    pub pot: Chips,
+    /// Chips already pooled in the middle
+    pub pooled_pot: Chips,
+    /// 0 preflop to 3 on the river
+    pub street: u8,
+    pub last_full_raise: LastFullRaise,
+    /// Seat index of the player to act
+    pub in_action: Option<usize>,
+    pub phase: Phase,
+    /// Actions of the current handout, in order
+    pub history: Vec<ActionRecord>,
+    /// Present only in tournament mode
+    pub tournament: Option<TournamentState>,
+    /// Time since the table opened, kept up to date by the caller. Read only by time-based
+    /// blind levels.
+    pub clock: Duration,
+    /// Seeds the deck and the bots of the current handout, and the seed of the next one.
+    /// Given as [[TableConfig::seed]], it deals the same handout first.
+    pub hand_seed: u64,
+    rng: ChaCha8Rng,
+    deck: Deck,
+    /// Dealt instead of random cards in the next handout
+    scenario: Option<Scenario>,
+}
+
+/// Engine entry point: the state after `action` and what happened, `state` being untouched
+pub fn step(state: &GameState, action: Action) -> Result<(GameState, Vec<GameEvent>), ActionError> {
+    let mut next_state = state.clone();
+    let events = next_state.apply_action(action)?;
+    Ok((next_state, events))
+}
+
+impl GameState {
+    /// Seats everyone with a fresh stack and deals the first handout.
+    /// The player in [[TableConfig::hero_seat]] starts in the big blind.
+    pub fn new(config: TableConfig, names: &[String]) -> Self {
+        let mut state = Self::seated(config, names);
+        state.start_handout();
+        state
+    }
+
+    /// Like [[GameState::new]], the first handout starting as set by `scenario`.
+    /// `names` are for the seats of the configured scenario.
+    pub fn from_scenario(
+        config: TableConfig,
+        names: &[String],
+        scenario: &Scenario,
+    ) -> Result<Self, ScenarioError> {
+        let mut config = config;
+        scenario.configure(&mut config);
+        scenario.validate(&config)?;
+        let mut state = Self::seated(config, names);
+        for (seat_index, stack) in scenario.stacks.iter() {
+            state.seats[*seat_index as usize].stack = *stack;
+        }
+        if let Some(button_seat) = scenario.button_seat {
+            let n_seats = state.config.n_seats;
+            let button_position = if n_seats == 2 { 1 } else { 2 };
+            let big_blind_seat = (button_seat + button_position) % n_seats;
+            for (seat_index, seat) in state.seats.iter_mut().enumerate() {
+                seat.preflop_position = (big_blind_seat + n_seats - seat_index as u8) % n_seats;
+            }
+        }
+        state.scenario = Some(scenario.clone());
+        state.start_handout();
+        Ok(state)
+    }
+
+    /// Everyone seated, no handout dealt yet
+    fn seated(config: TableConfig, names: &[String]) -> Self {
+        assert!(
+            (MIN_SEATS..=MAX_SEATS).contains(&config.n_seats),
+            "Table size must be from {} to {} seats",
+            MIN_SEATS,
+            MAX_SEATS
+        );
+        assert_eq!(names.len(), config.n_seats as usize, "One name per seat");
+        let mut config = config;
+        let n_seats = config.n_seats;
+        let tournament = config.tournament.clone().map(|tournament| {
+            config.apply_blind_level(&tournament.levels[0]);
+            TournamentState::new(n_seats, Duration::ZERO)
+        });
+        let bankroll = if config.tournament.is_some() {
+            Chips(0)
+        } else {
+            Chips(10 * config.buy_in().0)
+        };
+        let hero_seat = config.hero_seat();
+        let seats = names
+            .iter()
+            .enumerate()
+            .map(|(seat_index, name)| Seat {
+                name: name.clone(),
+                bankroll,
+                stack: config.buy_in(),
+                front: Chips(0),
+                contribution: Chips(0),
+                hole_cards: vec![Card::default(); config.variant.n_hole_cards()],
+                // Positions go down clockwise
+                preflop_position: (hero_seat + n_seats - seat_index as u8) % n_seats,
+                is_active: false,
+                is_all_in: false,
+                has_acted: false,
+                is_eliminated: false,
+                shows_cards: false,
+            })
+            .collect();
+        let hand_seed = config.seed.unwrap_or_else(rand::random);
+        let mut rng = ChaCha8Rng::seed_from_u64(hand_seed);
+        Self {
+            deck: Deck::new_shuffled(config.variant, &mut rng),
+            hand_seed,
+            rng,
+            last_full_raise: LastFullRaise::postflop(&config),
+            config,
+            seats,
+            board: Default::default(),
+            pot: Chips(0),
+            pooled_pot: Chips(0),
+            street: 0,
+            in_action: None,
+            phase: Phase::HandoutEnded,
+            history: Vec::new(),
+            tournament,
+            clock: Duration::ZERO,
+            scenario: None,
+        }
+    }
+
+    pub fn betting(&self) -> Betting {
+        Betting::new(self)
+    }
+
+    /// Runs `decide` with the random generator of the handout, so that bot decisions replay
+    /// along with the cards from [[GameState::hand_seed]]
+    pub fn with_rng<T>(&mut self, decide: impl FnOnce(&GameState, &mut ChaCha8Rng) -> T) -> T {
+        let mut rng = std::mem::replace(&mut self.rng, ChaCha8Rng::seed_from_u64(0));
+        let result = decide(self, &mut rng);
+        self.rng = rng;
+        result
+    }
+
+    /// [[None]] when nobody is to act
+    pub fn legal_actions(&self) -> Option<LegalActions> {
+        self.in_action.map(|_| self.betting().legal_actions())
+    }
+
+    /// Number of players dealt in the current handout
+    pub fn n_players(&self) -> u8 {
+        self.seats.iter().filter(|seat| !seat.is_eliminated).count() as u8
+    }
+
+    /// Action of the player in action
+    pub fn apply_action(&mut self, action: Action) -> Result<Vec<GameEvent>, ActionError> {
+        let actor_index = match (self.phase, self.in_action) {
+            (Phase::Betting, Some(actor_index)) => actor_index,
+            _ => return Err(ActionError::NotInAction),
+        };
+        let betting = self.betting();
+        betting.legal_actions().validate(&action)?;
+        self.last_full_raise = betting.last_full_raise_after(&action);
+
+        let actor = &mut self.seats[actor_index];
+        if action == Action::Fold {
+            actor.is_active = false;
+        } else {
+            // A player cannot move more than the stack
+            let chips_to_move = action.chips_to_move(actor.front.0).min(actor.stack.0);
+            actor.front.0 += chips_to_move;
+            actor.contribution.0 += chips_to_move;
+            actor.stack.0 -= chips_to_move;
+            self.pot.0 += chips_to_move;
+            if actor.stack.0 == 0 {
+                actor.is_all_in = true;
+            }
+        }
+        actor.has_acted = true;
+        self.history.push(ActionRecord {
+            street: self.street,
+            seat_index: actor_index,
+            action,
+        });
+
+        // The next actor is searched from the current one
+        let next_actor = self.betting().next_actor();
+        self.in_action = next_actor;
+        self.phase = match next_actor {
+            Some(_) => Phase::Betting,
+            None => Phase::BettingRoundOver,
+        };
+        Ok(vec![GameEvent::PlayerActed {
+            seat_index: actor_index,
+            action,
+        }])
+    }
+
+    /// Carries out the phase that needs no decision. Does nothing while betting or once the
+    /// tournament is over.
+    pub fn advance(&mut self) -> Vec<GameEvent> {
+        match self.phase {
+            Phase::Betting | Phase::TournamentFinished => vec![],
+            Phase::BettingRoundOver => self.pool_fronts(),
+            Phase::Dealing => self.deal_community_cards(),
+            Phase::Showdown => self.show_cards(),
+            Phase::Settling => self.settle_pots(),
+            Phase::HandoutEnded => self.start_next_handout(),
+        }
+    }
+
+    fn pool_fronts(&mut self) -> Vec<GameEvent> {
+        self.last_full_raise = LastFullRaise::postflop(&self.config);
+        for seat in self.seats.iter_mut() {
+            seat.has_acted = false;
+            self.pooled_pot.0 += seat.front.0;
+            seat.front.0 = 0;
+        }
+        let n_active = self.seats.iter().filter(|seat| seat.is_active).count();
+        self.phase = if n_active == 1 {
+            Phase::Settling
+        } else if self.street == 3 {
+            Phase::Showdown
+        } else {
+            Phase::Dealing
+        };
+        vec![GameEvent::ChipsPooled]
+    }
+
+    fn deal_community_cards(&mut self) -> Vec<GameEvent> {
+        let cards = &mut self.board;
+        if !cards[0].is_defined() {
+            // Preflop
+            cards[0] = self.deck.draw();
+            cards[1] = self.deck.draw();
+            cards[2] = self.deck.draw();
+            self.street = 1;
+        } else if !cards[3].is_defined() {
+            // Flop
+            cards[3] = self.deck.draw();
+            self.street = 2;
+        } else if !cards[4].is_defined() {
+            // Turn
+            cards[4] = self.deck.draw();
+            self.street = 3;
+        } else {
+            // River
+            unreachable!();
+        }
+        self.start_betting_round();
+        vec![GameEvent::CardsDealt {
+            street: self.street,
+        }]
+    }
+
+    fn show_cards(&mut self) -> Vec<GameEvent> {
+        let mut events = Vec::new();
+        for (seat_index, seat) in self.seats.iter_mut().enumerate() {
+            if seat.is_active {
+                seat.shows_cards = true;
+                events.push(GameEvent::CardsShown { seat_index });
+            }
+        }
+        self.phase = Phase::Settling;
+        events
+    }
+
+    fn settle_pots(&mut self) -> Vec<GameEvent> {
+        let contributions: Vec<u32> = self.seats.iter().map(|seat| seat.contribution.0).collect();
+        let is_active: Vec<bool> = self.seats.iter().map(|seat| seat.is_active).collect();
+        let pots = build_pots(&contributions, &is_active);
+
+        let winnings = settle_pots(&pots, self.seats.len(), |eligible| {
+            let hole_cards: Vec<&[Card]> = eligible
+                .iter()
+                .map(|&i| self.seats[i].hole_cards.as_slice())
+                .collect();
+            compare_hands(&hole_cards, &self.board, self.config.variant)
+                .into_iter()
+                .map(|winning_hand_index| eligible[winning_hand_index])
+                .collect()
+        });
+
+        let mut events = Vec::new();
+        for (seat_index, seat) in self.seats.iter_mut().enumerate() {
+            seat.front.0 += winnings[seat_index];
+            if winnings[seat_index] > 0 {
+                events.push(GameEvent::PotWon {
+                    seat_index,
+                    amount: Chips(winnings[seat_index]),
+                });
+            }
+        }
+        self.pot.0 = 0;
+        self.phase = Phase::HandoutEnded;
+        events
+    }
+
+    /// Settles the previous handout, busts or tops up players, then deals the next one
+    fn start_next_handout(&mut self) -> Vec<GameEvent> {
+        let mut events = Vec::new();
+        let mut busted: Vec<(u32, usize)> = Vec::new();
+        for (seat_index, seat) in self.seats.iter_mut().enumerate() {
+            // The front here may contain winnings
+            seat.stack.0 += seat.front.0;
+            seat.front.0 = 0;
+            let previous_contribution = seat.contribution.0;
+            seat.contribution.0 = 0;
+
+            if seat.is_eliminated {
+                continue;
+            }
+            if self.config.tournament.is_some() {
+                if seat.stack.0 == 0 {
+                    busted.push((previous_contribution, seat_index));
+                }
+            } else if seat.stack.0 < self.config.big_blind.0 {
+                let top_up_amount = (self.config.buy_in().0 - seat.stack.0).min(seat.bankroll.0);
+                seat.stack.0 += top_up_amount;
+                seat.bankroll.0 -= top_up_amount;
+            }
+        }
+
+        if let (Some(tournament), Some(tournament_state)) =
+            (self.config.tournament.clone(), self.tournament.as_mut())
+        {
+            // Of players busted in the same handout, the one who started with more finishes
+            // higher
+            busted.sort_by_key(|(previous_contribution, _)| *previous_contribution);
+            for &(_, seat_index) in busted.iter() {
+                let seat = &mut self.seats[seat_index];
+                let place = tournament_state.next_place();
+                tournament_state.eliminate(seat_index as u8, &seat.name);
+                seat.is_eliminated = true;
+                seat.is_active = false;
+                seat.is_all_in = false;
+                seat.has_acted = false;
+                events.push(GameEvent::PlayerEliminated { seat_index, place });
+            }
+            let remaining: Vec<usize> = (0..self.seats.len())
+                .filter(|&seat_index| !self.seats[seat_index].is_eliminated)
+                .collect();
+            if remaining.len() == 1 {
+                let winner_seat_index = remaining[0];
+                tournament_state.finish(
+                    &tournament,
+                    winner_seat_index as u8,
+                    &self.seats[winner_seat_index].name,
+                );
+                self.in_action = None;
+                self.phase = Phase::TournamentFinished;
+                events.push(GameEvent::TournamentFinished { winner_seat_index });
+                return events;
+            }
+            if tournament_state.advance(&tournament, self.clock) {
+                let level = tournament.levels[tournament_state.level];
+                self.config.apply_blind_level(&level);
+                events.push(GameEvent::BlindsUp(level));
+            }
+        }
+
+        let seats: Vec<(u8, bool)> = self
+            .seats
+            .iter()
+            .map(|seat| (seat.preflop_position, !seat.is_eliminated))
+            .collect();
+        let next_positions = next_preflop_positions(&seats);
+        for (seat, next_position) in self.seats.iter_mut().zip(next_positions) {
+            seat.preflop_position = next_position;
+        }
+        self.hand_seed = self.rng.gen();
+        events.extend(self.start_handout());
+        events
+    }
+
+    /// Posts forced bets and deals hole cards to the players not eliminated
+    fn start_handout(&mut self) -> Vec<GameEvent> {
+        self.rng = ChaCha8Rng::seed_from_u64(self.hand_seed);
+        self.deck = match self.scenario.take() {
+            Some(scenario) => {
+                let dealt_in: Vec<bool> =
+                    self.seats.iter().map(|seat| !seat.is_eliminated).collect();
+                let draw_order = scenario.draw_order(&dealt_in, self.config.variant.n_hole_cards());
+                Deck::new_preset(self.config.variant, &mut self.rng, &draw_order)
+            }
+            None => Deck::new_shuffled(self.config.variant, &mut self.rng),
+        };
+        let n_players = self.n_players();
+        self.pot = Chips(0);
+        self.pooled_pot = Chips(0);
+        self.history.clear();
+        for seat in self.seats.iter_mut() {
+            seat.shows_cards = false;
+            seat.has_acted = false;
+            if seat.is_eliminated {
+                for card in seat.hole_cards.iter_mut() {
+                    *card = Card::default();
+                }
+                continue;
+            }
+            let (blind, ante) = self.config.forced_bets(seat.preflop_position, n_players);
+            // A short stack posts what it has, ante first
+            let ante = ante.0.min(seat.stack.0);
+            seat.stack.0 -= ante;
+            let blind = blind.0.min(seat.stack.0);
+            seat.stack.0 -= blind;
+            seat.front.0 += blind;
+            seat.contribution.0 += blind + ante;
+            self.pot.0 += blind + ante;
+            self.pooled_pot.0 += ante;
+            seat.is_active = true;
+            seat.is_all_in = seat.stack.0 == 0;
+            for card in seat.hole_cards.iter_mut() {
+                *card = self.deck.draw();
+            }
+        }
+        self.board = Default::default();
+        self.street = 0;
+        self.last_full_raise = LastFullRaise::preflop(&self.config, n_players);
+        self.start_betting_round();
+        vec![GameEvent::HandoutStarted {
+            seed: self.hand_seed,
+        }]
+    }
+
+    /// First player to act on the street, if betting is still possible
+    fn start_betting_round(&mut self) {
+        self.in_action = None;
+        self.in_action = self.betting().next_actor();
+        self.phase = match self.in_action {
+            Some(_) => Phase::Betting,
+            // No more betting possible: straight to the next street or showdown
+            None => Phase::BettingRoundOver,
+        };
+    }
+}
+
+/// Moves the big blind to the next player dealt in, clockwise.
+///
+/// `seats` holds `(current preflop position, is dealt in)` by seat index. Players not dealt in
+/// get positions after those of the players dealt in.
+fn next_preflop_positions(seats: &[(u8, bool)]) -> Vec<u8> {
+    let n_seats = seats.len();
+    let n_players = seats.iter().filter(|(_, is_dealt_in)| *is_dealt_in).count();
+    let big_blind_seat = seats
+        .iter()
+        .position(|(position, _)| *position == 0)
+        .unwrap_or(0);
+    let mut positions = vec![0; n_seats];
+    let mut n_dealt_in = 0;
+    let mut n_sitting_out = 0;
+    for i in 1..=n_seats {
+        let seat = (big_blind_seat + i) % n_seats;
+        if seats[seat].1 {
+            // Big blind first, then positions go down clockwise
+            positions[seat] = ((n_players - n_dealt_in) % n_players) as u8;
+            n_dealt_in += 1;
+        } else {
+            positions[seat] = (n_players + n_sitting_out) as u8;
+            n_sitting_out += 1;
+        }
+    }
+    positions
+}
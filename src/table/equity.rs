@@ -0,0 +1,534 @@
+use std::fmt;
+
+use rand::{
+    distributions::{Distribution, WeightedIndex},
+    seq::SliceRandom,
+    SeedableRng,
+};
+use rand_chacha::ChaCha8Rng;
+
+use super::{
+    compare_hands::compare_hands,
+    components::Card,
+    range::{Combo, Range},
+    resources::GameVariant,
+};
+
+/// How equity is computed
+#[derive(Debug, Clone)]
+pub struct EquityConfig {
+    pub variant: GameVariant,
+    /// Boards are enumerated when there are at most this many run-outs, sampled otherwise
+    pub max_exact_boards: u64,
+    /// Run-outs dealt by Monte Carlo
+    pub n_samples: u64,
+    /// Monte Carlo seed, random if [[None]]
+    pub seed: Option<u64>,
+}
+
+impl Default for EquityConfig {
+    fn default() -> Self {
+        Self {
+            variant: GameVariant::Holdem,
+            max_exact_boards: 200_000,
+            n_samples: 100_000,
+            seed: None,
+        }
+    }
+}
+
+/// Percentages of the run-outs, ties counted apart from wins.
+/// Equity counts a tie between `n` players as 1/n of a win.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct PlayerEquity {
+    pub win: f64,
+    pub tie: f64,
+    pub equity: f64,
+}
+
+#[derive(Debug, Clone)]
+pub struct EquityResult {
+    /// In the order of the hands given
+    pub players: Vec<PlayerEquity>,
+    pub n_boards: u64,
+    /// Every run-out enumerated, no sampling
+    pub is_exact: bool,
+}
+
+/// Showdown results summed over run-outs, each weighing as likely as it is
+#[derive(Debug, Clone)]
+struct Tally {
+    wins: Vec<f64>,
+    ties: Vec<f64>,
+    shares: Vec<f64>,
+    total_weight: f64,
+    n_boards: u64,
+}
+
+impl Tally {
+    fn new(n_players: usize) -> Self {
+        Self {
+            wins: vec![0.0; n_players],
+            ties: vec![0.0; n_players],
+            shares: vec![0.0; n_players],
+            total_weight: 0.0,
+            n_boards: 0,
+        }
+    }
+
+    /// Counts the showdown of `hole_cards` on a full `board`
+    fn add_showdown(
+        &mut self,
+        hole_cards: &[&[Card]],
+        board: &[Card],
+        variant: GameVariant,
+        weight: f64,
+    ) {
+        let winners = compare_hands(hole_cards, board, variant);
+        let share = weight / winners.len() as f64;
+        for &winner in winners.iter() {
+            if winners.len() == 1 {
+                self.wins[winner] += weight;
+            } else {
+                self.ties[winner] += weight;
+            }
+            self.shares[winner] += share;
+        }
+        self.total_weight += weight;
+        self.n_boards += 1;
+    }
+
+    fn result(&self, is_exact: bool) -> EquityResult {
+        let percentage = |weight: f64| {
+            if self.total_weight == 0.0 {
+                0.0
+            } else {
+                weight / self.total_weight * 100.0
+            }
+        };
+        let players = (0..self.wins.len())
+            .map(|player_index| PlayerEquity {
+                win: percentage(self.wins[player_index]),
+                tie: percentage(self.ties[player_index]),
+                equity: percentage(self.shares[player_index]),
+            })
+            .collect();
+        EquityResult {
+            players,
+            n_boards: self.n_boards,
+            is_exact,
+        }
+    }
+}
+
+impl EquityConfig {
+    fn rng(&self) -> ChaCha8Rng {
+        match self.seed {
+            Some(seed) => ChaCha8Rng::seed_from_u64(seed),
+            None => ChaCha8Rng::from_entropy(),
+        }
+    }
+}
+
+/// Win, tie and equity of every hand in `hole_cards` by the river. `board` holds the
+/// community cards already dealt, `dead_cards` are out of the deck.
+pub fn calculate_equity(
+    config: &EquityConfig,
+    hole_cards: &[Vec<Card>],
+    board: &[Card],
+    dead_cards: &[Card],
+) -> Result<EquityResult, EquityError> {
+    if hole_cards.len() < 2 {
+        return Err(EquityError::TooFewPlayers);
+    }
+    for (player_index, cards) in hole_cards.iter().enumerate() {
+        if cards.len() != config.variant.n_hole_cards() {
+            return Err(EquityError::WrongHoleCardCount {
+                player_index,
+                expected: config.variant.n_hole_cards(),
+                got: cards.len(),
+            });
+        }
+    }
+    if board.len() > 5 {
+        return Err(EquityError::TooManyBoardCards(board.len()));
+    }
+    let known_cards: Vec<&Card> = hole_cards
+        .iter()
+        .flatten()
+        .chain(board.iter())
+        .chain(dead_cards.iter())
+        .collect();
+    let deck = remaining_deck(config.variant, &known_cards)?;
+    let n_missing = 5 - board.len();
+    if deck.len() < n_missing {
+        return Err(EquityError::NotEnoughCards);
+    }
+
+    let hands: Vec<&[Card]> = hole_cards.iter().map(|cards| cards.as_slice()).collect();
+    let mut tally = Tally::new(hands.len());
+    let mut full_board: Vec<Card> = board.to_vec();
+    let is_exact = n_combinations(deck.len(), n_missing) <= config.max_exact_boards;
+    if is_exact {
+        for_each_combination(&deck, n_missing, &mut |run_out| {
+            full_board.truncate(board.len());
+            full_board.extend_from_slice(run_out);
+            tally.add_showdown(&hands, &full_board, config.variant, 1.0);
+        });
+    } else {
+        let mut rng = config.rng();
+        for _ in 0..config.n_samples {
+            full_board.truncate(board.len());
+            full_board.extend(deck.choose_multiple(&mut rng, n_missing).cloned());
+            tally.add_showdown(&hands, &full_board, config.variant, 1.0);
+        }
+    }
+    Ok(tally.result(is_exact))
+}
+
+/// Cards of the `variant` deck not in `known_cards`, which must all be distinct
+fn remaining_deck(variant: GameVariant, known_cards: &[&Card]) -> Result<Vec<Card>, EquityError> {
+    let mut is_known = [false; 52];
+    for card in known_cards {
+        if !card.is_defined() || card.rank() < variant.lowest_card_rank() {
+            return Err(EquityError::CardNotInDeck((*card).clone()));
+        }
+        if is_known[card.0 as usize] {
+            return Err(EquityError::DuplicateCard((*card).clone()));
+        }
+        is_known[card.0 as usize] = true;
+    }
+    Ok((variant.lowest_card_rank() * 4..52)
+        .filter(|&card| !is_known[card as usize])
+        .map(Card)
+        .collect())
+}
+
+/// Number of `k`-card subsets of `n` cards
+fn n_combinations(n: usize, k: usize) -> u64 {
+    if k > n {
+        return 0;
+    }
+    (0..k as u64).fold(1, |combinations, i| combinations * (n as u64 - i) / (i + 1))
+}
+
+/// Calls `f` with every `k`-card subset of `cards`
+fn for_each_combination(cards: &[Card], k: usize, f: &mut impl FnMut(&[Card])) {
+    fn recurse(cards: &[Card], k: usize, chosen: &mut Vec<Card>, f: &mut impl FnMut(&[Card])) {
+        if chosen.len() == k {
+            f(chosen);
+            return;
+        }
+        let n_needed = k - chosen.len();
+        for i in 0..=cards.len() - n_needed {
+            chosen.push(cards[i].clone());
+            recurse(&cards[i + 1..], k, chosen, f);
+            chosen.pop();
+        }
+    }
+    recurse(cards, k, &mut Vec::with_capacity(k), f);
+}
+
+/// Like [[calculate_equity]] with a weighted range for every player. Combos holding a known
+/// card, or a card of another player, are left out.
+pub fn range_equity(
+    config: &EquityConfig,
+    ranges: &[Range],
+    board: &[Card],
+    dead_cards: &[Card],
+) -> Result<EquityResult, EquityError> {
+    if config.variant.n_hole_cards() != 2 {
+        return Err(EquityError::RangesNeedTwoCards);
+    }
+    if ranges.len() < 2 {
+        return Err(EquityError::TooFewPlayers);
+    }
+    if board.len() > 5 {
+        return Err(EquityError::TooManyBoardCards(board.len()));
+    }
+    let known_cards: Vec<&Card> = board.iter().chain(dead_cards.iter()).collect();
+    let deck = remaining_deck(config.variant, &known_cards)?;
+    let deck_mask = deck.iter().fold(0u64, |mask, card| mask | 1 << card.0);
+    let combos: Vec<Vec<(Combo, f64)>> = ranges
+        .iter()
+        .map(|range| {
+            range
+                .without_cards(!deck_mask)
+                .combos()
+                .map(|(combo, weight)| (combo, weight as f64))
+                .collect()
+        })
+        .collect();
+    if let Some(player_index) = combos.iter().position(Vec::is_empty) {
+        return Err(EquityError::EmptyRange(player_index));
+    }
+    let n_missing = 5 - board.len();
+    if deck.len() < n_missing + 2 * ranges.len() {
+        return Err(EquityError::NotEnoughCards);
+    }
+
+    let mut tally = Tally::new(ranges.len());
+    let mut full_board: Vec<Card> = board.to_vec();
+    let n_deals = combos
+        .iter()
+        .map(|combos| combos.len() as u64)
+        .product::<u64>();
+    let n_run_outs = n_combinations(deck.len() - 2 * ranges.len(), n_missing);
+    let is_exact = n_deals.saturating_mul(n_run_outs) <= config.max_exact_boards;
+    if is_exact {
+        for_each_deal(&combos, 0, 1.0, &mut Vec::new(), &mut |deal, weight| {
+            let dealt_mask = deal.iter().fold(0, |mask, combo| mask | combo.mask());
+            let rest: Vec<Card> = deck
+                .iter()
+                .filter(|card| dealt_mask & 1 << card.0 == 0)
+                .cloned()
+                .collect();
+            let hole_cards: Vec<[Card; 2]> = deal.iter().map(Combo::cards).collect();
+            let hands: Vec<&[Card]> = hole_cards.iter().map(|cards| cards.as_slice()).collect();
+            for_each_combination(&rest, n_missing, &mut |run_out| {
+                full_board.truncate(board.len());
+                full_board.extend_from_slice(run_out);
+                tally.add_showdown(&hands, &full_board, config.variant, weight);
+            });
+        });
+        if tally.total_weight == 0.0 {
+            return Err(EquityError::NoCompatibleHands);
+        }
+    } else {
+        let mut rng = config.rng();
+        let samplers: Vec<WeightedIndex<f64>> = combos
+            .iter()
+            .map(|combos| WeightedIndex::new(combos.iter().map(|(_, weight)| *weight)).unwrap())
+            .collect();
+        for _ in 0..config.n_samples {
+            // Hands sampled independently, a deal with a card twice is dealt again
+            let mut n_rejected = 0;
+            let (deal, dealt_mask) = loop {
+                let deal: Vec<Combo> = samplers
+                    .iter()
+                    .zip(combos.iter())
+                    .map(|(sampler, combos)| combos[sampler.sample(&mut rng)].0)
+                    .collect();
+                let dealt_mask = deal.iter().try_fold(0u64, |mask, combo| {
+                    (mask & combo.mask() == 0).then_some(mask | combo.mask())
+                });
+                if let Some(dealt_mask) = dealt_mask {
+                    break (deal, dealt_mask);
+                }
+                n_rejected += 1;
+                if n_rejected == MAX_REJECTED_DEALS {
+                    return Err(EquityError::NoCompatibleHands);
+                }
+            };
+            let rest: Vec<Card> = deck
+                .iter()
+                .filter(|card| dealt_mask & 1 << card.0 == 0)
+                .cloned()
+                .collect();
+            let hole_cards: Vec<[Card; 2]> = deal.iter().map(Combo::cards).collect();
+            let hands: Vec<&[Card]> = hole_cards.iter().map(|cards| cards.as_slice()).collect();
+            full_board.truncate(board.len());
+            full_board.extend(rest.choose_multiple(&mut rng, n_missing).cloned());
+            tally.add_showdown(&hands, &full_board, config.variant, 1.0);
+        }
+    }
+    Ok(tally.result(is_exact))
+}
+
+/// Equity of known hole cards against a range
+pub fn hand_vs_range_equity(
+    config: &EquityConfig,
+    hole_cards: &[Card],
+    range: &Range,
+    board: &[Card],
+    dead_cards: &[Card],
+) -> Result<EquityResult, EquityError> {
+    let combo = match hole_cards {
+        [a, b] => Combo::new(a, b).ok_or_else(|| EquityError::DuplicateCard(a.clone()))?,
+        _ => {
+            return Err(EquityError::WrongHoleCardCount {
+                player_index: 0,
+                expected: 2,
+                got: hole_cards.len(),
+            })
+        }
+    };
+    range_equity(
+        config,
+        &[Range::from_combo(combo), range.clone()],
+        board,
+        dead_cards,
+    )
+}
+
+/// Monte Carlo gives up on ranges that almost never fit together
+const MAX_REJECTED_DEALS: u32 = 100_000;
+
+/// Calls `f` with every deal of one combo per player without a card twice, and the product
+/// of the combo weights
+fn for_each_deal(
+    combos: &[Vec<(Combo, f64)>],
+    dealt_mask: u64,
+    weight: f64,
+    deal: &mut Vec<Combo>,
+    f: &mut impl FnMut(&[Combo], f64),
+) {
+    let Some((player_combos, other_combos)) = combos.split_first() else {
+        f(deal, weight);
+        return;
+    };
+    for (combo, combo_weight) in player_combos.iter() {
+        if dealt_mask & combo.mask() != 0 {
+            continue;
+        }
+        deal.push(*combo);
+        for_each_deal(
+            other_combos,
+            dealt_mask | combo.mask(),
+            weight * combo_weight,
+            deal,
+            f,
+        );
+        deal.pop();
+    }
+}
+
+/// Why equity cannot be computed
+#[derive(Debug, Clone)]
+pub enum EquityError {
+    TooFewPlayers,
+    WrongHoleCardCount {
+        player_index: usize,
+        expected: usize,
+        got: usize,
+    },
+    TooManyBoardCards(usize),
+    /// Undefined, or below the lowest rank of a short deck
+    CardNotInDeck(Card),
+    DuplicateCard(Card),
+    /// Not enough cards left in the deck to complete the board
+    NotEnoughCards,
+    /// Ranges are of two-card hands
+    RangesNeedTwoCards,
+    /// No combo of the range of this player is left once known cards are removed
+    EmptyRange(usize),
+    /// Ranges always share a card
+    NoCompatibleHands,
+}
+
+impl fmt::Display for EquityError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EquityError::TooFewPlayers => write!(f, "equity needs at least two hands"),
+            EquityError::WrongHoleCardCount {
+                player_index,
+                expected,
+                got,
+            } => write!(
+                f,
+                "hand {} has {} cards, expected {}",
+                player_index + 1,
+                got,
+                expected
+            ),
+            EquityError::TooManyBoardCards(n_cards) => {
+                write!(f, "{} board cards, at most 5", n_cards)
+            }
+            EquityError::CardNotInDeck(card) => write!(f, "{} is not in the deck", card),
+            EquityError::DuplicateCard(card) => write!(f, "{} is dealt twice", card),
+            EquityError::NotEnoughCards => write!(f, "not enough cards left to deal the board"),
+            EquityError::RangesNeedTwoCards => write!(f, "ranges are of two-card hands"),
+            EquityError::EmptyRange(player_index) => write!(
+                f,
+                "range {} has no combo left once known cards are removed",
+                player_index + 1
+            ),
+            EquityError::NoCompatibleHands => {
+                write!(f, "ranges cannot be dealt without sharing a card")
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::table::components::parse_cards;
+
+    fn cards(text: &str) -> Vec<Card> {
+        parse_cards(text).unwrap()
+    }
+
+    fn exact(variant: GameVariant) -> EquityConfig {
+        EquityConfig {
+            variant,
+            max_exact_boards: u64::MAX,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn aces_against_kings() {
+        let config = exact(GameVariant::Holdem);
+        let result = calculate_equity(&config, &[cards("AsAh"), cards("KdKc")], &[], &[]).unwrap();
+        assert!(result.is_exact);
+        assert_eq!(result.n_boards, 1_712_304);
+        let aces = result.players[0];
+        assert!((81.0..83.0).contains(&aces.equity), "{:?}", aces);
+        let total: f64 = result.players.iter().map(|player| player.equity).sum();
+        assert!((total - 100.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn board_plays() {
+        let config = exact(GameVariant::Holdem);
+        let board = cards("AsKsQsJsTs");
+        let result =
+            calculate_equity(&config, &[cards("2c3d"), cards("4h5h")], &board, &[]).unwrap();
+        assert_eq!(result.n_boards, 1);
+        for player in result.players {
+            assert_eq!(player.win, 0.0);
+            assert_eq!(player.tie, 100.0);
+            assert_eq!(player.equity, 50.0);
+        }
+    }
+
+    #[test]
+    fn range_against_range() {
+        let config = exact(GameVariant::Holdem);
+        let ranges: Vec<Range> = ["AA", "KK"]
+            .iter()
+            .map(|text| text.parse().unwrap())
+            .collect();
+        let result = range_equity(&config, &ranges, &cards("2c7d9h"), &[]).unwrap();
+        assert!(result.is_exact);
+        assert!((88.0..93.0).contains(&result.players[0].equity));
+    }
+
+    #[test]
+    fn errors() {
+        let config = exact(GameVariant::Holdem);
+        assert!(matches!(
+            calculate_equity(&config, &[cards("AsAh"), cards("AsKd")], &[], &[]),
+            Err(EquityError::DuplicateCard(card)) if card.0 == cards("As")[0].0
+        ));
+        assert!(matches!(
+            calculate_equity(
+                &exact(GameVariant::ShortDeck),
+                &[cards("AsAh"), cards("Kd2c")],
+                &[],
+                &[]
+            ),
+            Err(EquityError::CardNotInDeck(card)) if card.0 == cards("2c")[0].0
+        ));
+        assert!(matches!(
+            calculate_equity(
+                &config,
+                &[cards("AsAh"), cards("KdKc")],
+                &cards("2c3c4c5c6c7c"),
+                &[]
+            ),
+            Err(EquityError::TooManyBoardCards(6))
+        ));
+    }
+}
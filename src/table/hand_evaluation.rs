@@ -30,15 +30,77 @@ pub enum Rank {
     StraightFlush(u32),
 }
 
+impl Rank {
+    /// Category from the weakest, used to reorder hands in short-deck
+    fn category(&self) -> u8 {
+        match self {
+            Rank::HighCard(_) => 0,
+            Rank::OnePair(_) => 1,
+            Rank::TwoPair(_) => 2,
+            Rank::ThreeOfAKind(_) => 3,
+            Rank::Straight(_) => 4,
+            Rank::Flush(_) => 5,
+            Rank::FullHouse(_) => 6,
+            Rank::FourOfAKind(_) => 7,
+            Rank::StraightFlush(_) => 8,
+        }
+    }
+
+    fn value(&self) -> u32 {
+        match *self {
+            Rank::HighCard(v)
+            | Rank::OnePair(v)
+            | Rank::TwoPair(v)
+            | Rank::ThreeOfAKind(v)
+            | Rank::Straight(v)
+            | Rank::Flush(v)
+            | Rank::FullHouse(v)
+            | Rank::FourOfAKind(v)
+            | Rank::StraightFlush(v) => v,
+        }
+    }
+}
+
+/// Short-deck ordering of a `Rank`: a flush beats a full house.
+#[derive(Debug, PartialEq, Eq, Clone, Hash, Copy)]
+pub struct ShortDeckRank(pub Rank);
+
+impl ShortDeckRank {
+    fn key(&self) -> (u8, u32) {
+        let category = match self.0 {
+            Rank::Flush(_) => 6,
+            Rank::FullHouse(_) => 5,
+            rank => rank.category(),
+        };
+        (category, self.0.value())
+    }
+}
+
+impl Ord for ShortDeckRank {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.key().cmp(&other.key())
+    }
+}
+
+impl PartialOrd for ShortDeckRank {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
 /// Bit mask for the wheel (Ace, two, three, four, five)
 const WHEEL: u32 = 0b1_0000_0000_1111;
+/// Bit mask for the short-deck wheel (Ace, six, seven, eight, nine)
+const SHORT_DECK_WHEEL: u32 = 0b1_0000_1111_0000;
 /// Given a bitset of hand ranks. This method
 /// will determine if there's a straight, and will give the
 /// rank. Wheel is the lowest, broadway is the highest value.
 ///
+/// `wheel` is the lowest straight: `WHEEL`, or `SHORT_DECK_WHEEL`.
+///
 /// Returns None if the hand ranks represented don't correspond
 /// to a straight.
-fn rank_straight(value_set: u32) -> Option<u32> {
+fn rank_straight(value_set: u32, wheel: u32) -> Option<u32> {
     // Example of something with a straight:
     //       0000111111100
     //       0001111111000
@@ -67,7 +129,7 @@ fn rank_straight(value_set: u32) -> Option<u32> {
     // If this isn't all zeros then we found a straight
     if idx < 32 {
         Some(32 - 4 - idx)
-    } else if value_set & WHEEL == WHEEL {
+    } else if value_set & wheel == wheel {
         // Check to see if this is the wheel. It's pretty unlikely.
         Some(0)
     } else {
@@ -94,6 +156,83 @@ fn keep_n(rank: u32, to_keep: u32) -> u32 {
 fn find_flush(suit_value_sets: &[u32]) -> Option<usize> {
     suit_value_sets.iter().position(|sv| sv.count_ones() >= 5)
 }
+/// Best 5 card hand of 5 cards or more, the lowest straight given by `wheel`
+fn rank_cards(cards: &[Card], wheel: u32) -> Rank {
+    let mut value_to_count: [u8; 13] = [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+    let mut count_to_value: [u32; 5] = [0, 0, 0, 0, 0];
+    let mut suit_value_sets: [u32; 4] = [0, 0, 0, 0];
+    let mut value_set: u32 = 0;
+
+    for c in cards {
+        let v = c.value as u8;
+        let s = c.suit as u8;
+        value_set |= 1 << v;
+        value_to_count[v as usize] += 1;
+        suit_value_sets[s as usize] |= 1 << v;
+    }
+
+    // Now rotate the value to count map.
+    for (value, &count) in value_to_count.iter().enumerate() {
+        count_to_value[count as usize] |= 1 << value;
+    }
+
+    // Find out if there's a flush
+    let flush: Option<usize> = find_flush(&suit_value_sets);
+
+    // If this is a flush then it could be a straight flush
+    // or a flush. So check only once.
+    if let Some(flush_idx) = flush {
+        // If we can find a straight in the flush then it's a straight flush
+        if let Some(rank) = rank_straight(suit_value_sets[flush_idx], wheel) {
+            Rank::StraightFlush(rank)
+        } else {
+            // Else it's just a normal flush
+            let rank = keep_n(suit_value_sets[flush_idx], 5);
+            Rank::Flush(rank)
+        }
+    } else if count_to_value[4] != 0 {
+        // Four of a kind.
+        let high = keep_highest(value_set ^ count_to_value[4]);
+        Rank::FourOfAKind(count_to_value[4] << 13 | high)
+    } else if count_to_value[3] != 0 && count_to_value[3].count_ones() == 2 {
+        // There are two sets. So the best we can make is a full house.
+        let set = keep_highest(count_to_value[3]);
+        let pair = count_to_value[3] ^ set;
+        Rank::FullHouse(set << 13 | pair)
+    } else if count_to_value[3] != 0 && count_to_value[2] != 0 {
+        // there is a pair and a set.
+        let set = count_to_value[3];
+        let pair = keep_highest(count_to_value[2]);
+        Rank::FullHouse(set << 13 | pair)
+    } else if let Some(s_rank) = rank_straight(value_set, wheel) {
+        // If there's a straight return it now.
+        Rank::Straight(s_rank)
+    } else if count_to_value[3] != 0 {
+        // if there is a set then we need to keep 2 cards that
+        // aren't in the set.
+        let low = keep_n(value_set ^ count_to_value[3], 2);
+        Rank::ThreeOfAKind(count_to_value[3] << 13 | low)
+    } else if count_to_value[2].count_ones() >= 2 {
+        // Two pair
+        //
+        // That can be because we have 3 pairs and a high card.
+        // Or we could have two pair and two high cards.
+        let pairs = keep_n(count_to_value[2], 2);
+        let low = keep_highest(value_set ^ pairs);
+        Rank::TwoPair(pairs << 13 | low)
+    } else if count_to_value[2] == 0 {
+        // This means that there's no pair
+        // no sets, no straights, no flushes, so only a
+        // high card.
+        Rank::HighCard(keep_n(value_set, 5))
+    } else {
+        // Otherwise there's only one pair.
+        let pair = count_to_value[2];
+        // Keep the highest three cards not in the pair.
+        let low = keep_n(value_set ^ count_to_value[2], 3);
+        Rank::OnePair(pair << 13 | low)
+    }
+}
 /// Can this turn into a hand rank? There are default implementations for
 /// `Hand` and `Vec<Card>`.
 pub trait Rankable {
@@ -108,88 +247,23 @@ pub trait Rankable {
     ///
     /// # Examples
     /// ```
-    /// use rs_poker::core::{Hand, Rank, Rankable};
+    /// use poker_simulator::table::hand_evaluation::{Card, Rank, Rankable};
     ///
-    /// let hand = Hand::new_from_str("2h2d8d8sKd6sTh").unwrap();
+    /// // 2s 2c 8s 8c Kd 6s Th
+    /// let hand: Vec<Card> = [0, 1, 24, 25, 46, 16, 35].into_iter().map(Card::new).collect();
     /// let rank = hand.rank();
     /// assert!(Rank::TwoPair(0) <= rank);
     /// assert!(Rank::TwoPair(u32::max_value()) >= rank);
     /// ```
     fn rank(&self) -> Rank {
-        let mut value_to_count: [u8; 13] = [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0];
-        let mut count_to_value: [u32; 5] = [0, 0, 0, 0, 0];
-        let mut suit_value_sets: [u32; 4] = [0, 0, 0, 0];
-        let mut value_set: u32 = 0;
-
-        for c in self.cards() {
-            let v = c.value as u8;
-            let s = c.suit as u8;
-            value_set |= 1 << v;
-            value_to_count[v as usize] += 1;
-            suit_value_sets[s as usize] |= 1 << v;
-        }
-
-        // Now rotate the value to count map.
-        for (value, &count) in value_to_count.iter().enumerate() {
-            count_to_value[count as usize] |= 1 << value;
-        }
+        rank_cards(self.cards(), WHEEL)
+    }
 
-        // Find out if there's a flush
-        let flush: Option<usize> = find_flush(&suit_value_sets);
-
-        // If this is a flush then it could be a straight flush
-        // or a flush. So check only once.
-        if let Some(flush_idx) = flush {
-            // If we can find a straight in the flush then it's a straight flush
-            if let Some(rank) = rank_straight(suit_value_sets[flush_idx]) {
-                Rank::StraightFlush(rank)
-            } else {
-                // Else it's just a normal flush
-                let rank = keep_n(suit_value_sets[flush_idx], 5);
-                Rank::Flush(rank)
-            }
-        } else if count_to_value[4] != 0 {
-            // Four of a kind.
-            let high = keep_highest(value_set ^ count_to_value[4]);
-            Rank::FourOfAKind(count_to_value[4] << 13 | high)
-        } else if count_to_value[3] != 0 && count_to_value[3].count_ones() == 2 {
-            // There are two sets. So the best we can make is a full house.
-            let set = keep_highest(count_to_value[3]);
-            let pair = count_to_value[3] ^ set;
-            Rank::FullHouse(set << 13 | pair)
-        } else if count_to_value[3] != 0 && count_to_value[2] != 0 {
-            // there is a pair and a set.
-            let set = count_to_value[3];
-            let pair = keep_highest(count_to_value[2]);
-            Rank::FullHouse(set << 13 | pair)
-        } else if let Some(s_rank) = rank_straight(value_set) {
-            // If there's a straight return it now.
-            Rank::Straight(s_rank)
-        } else if count_to_value[3] != 0 {
-            // if there is a set then we need to keep 2 cards that
-            // aren't in the set.
-            let low = keep_n(value_set ^ count_to_value[3], 2);
-            Rank::ThreeOfAKind(count_to_value[3] << 13 | low)
-        } else if count_to_value[2].count_ones() >= 2 {
-            // Two pair
-            //
-            // That can be because we have 3 pairs and a high card.
-            // Or we could have two pair and two high cards.
-            let pairs = keep_n(count_to_value[2], 2);
-            let low = keep_highest(value_set ^ pairs);
-            Rank::TwoPair(pairs << 13 | low)
-        } else if count_to_value[2] == 0 {
-            // This means that there's no pair
-            // no sets, no straights, no flushes, so only a
-            // high card.
-            Rank::HighCard(keep_n(value_set, 5))
-        } else {
-            // Otherwise there's only one pair.
-            let pair = count_to_value[2];
-            // Keep the highest three cards not in the pair.
-            let low = keep_n(value_set ^ count_to_value[2], 3);
-            Rank::OnePair(pair << 13 | low)
-        }
+    /// Rank the cards to find the best 5 card hand with short-deck
+    /// straights: the ace plays below the six. Compare the results with
+    /// `ShortDeckRank`.
+    fn rank_short_deck(&self) -> Rank {
+        rank_cards(self.cards(), SHORT_DECK_WHEEL)
     }
 
     /// Rank this hand. It doesn't do any caching so it's left up to the user
@@ -235,7 +309,7 @@ pub trait Rankable {
                 // Need to check for all of them.
                 let suit_count = suit_set.count_ones();
                 let is_flush = suit_count == 1;
-                match (rank_straight(value_set), is_flush) {
+                match (rank_straight(value_set, WHEEL), is_flush) {
                     // This is the most likely outcome.
                     // Not a flush and not a straight.
                     (None, false) => Rank::HighCard(value_set),
@@ -307,3 +381,75 @@ impl Rankable for Vec<Card> {
         &self[..]
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// "Ah Kd ..." with suits in the order of the deck: spades, clubs, diamonds, hearts
+    fn cards(hand: &str) -> Vec<Card> {
+        hand.split_whitespace()
+            .map(|card| {
+                let mut chars = card.chars();
+                let value = "23456789TJQKA".find(chars.next().unwrap()).unwrap() as u8;
+                let suit = "scdh".find(chars.next().unwrap()).unwrap() as u8;
+                Card::new(value * 4 + suit)
+            })
+            .collect()
+    }
+
+    fn short_deck(hand: &str) -> ShortDeckRank {
+        ShortDeckRank(cards(hand).rank_short_deck())
+    }
+
+    #[test]
+    fn ace_six_seven_eight_nine_is_a_short_deck_straight() {
+        let hand = cards("As 6c 7d 8h 9s Jc Qd");
+        assert_eq!(hand.rank_short_deck(), Rank::Straight(0));
+        assert!(matches!(hand.rank(), Rank::HighCard(_)));
+    }
+
+    #[test]
+    fn ace_six_seven_eight_nine_is_the_lowest_short_deck_straight() {
+        assert!(short_deck("As 6c 7d 8h 9s Jc Qd") < short_deck("6c 7d 8h 9s Td Jc Kd"));
+        assert!(short_deck("As 6c 7d 8h 9s Jc Qd") > short_deck("As Ac Ad 8h 9s Jc Qd"));
+    }
+
+    #[test]
+    fn ace_six_seven_eight_nine_suited_is_a_short_deck_straight_flush() {
+        let hand = cards("Ah 6h 7h 8h 9h Jc Qd");
+        assert_eq!(hand.rank_short_deck(), Rank::StraightFlush(0));
+        assert_eq!(hand.rank(), Rank::Flush(0b1_0000_1111_0000));
+    }
+
+    #[test]
+    fn flush_beats_full_house_in_short_deck() {
+        let flush = short_deck("6h 8h 9h Jh Kh Ac Ad");
+        let full_house = short_deck("Ks Kc Kd Qh Qs 7c 6d");
+        assert!(flush > full_house);
+        assert!(flush.0 < full_house.0);
+    }
+
+    #[test]
+    fn other_categories_keep_their_order_in_short_deck() {
+        let four_of_a_kind = short_deck("9s 9c 9d 9h Ks 7c 6d");
+        let flush = short_deck("6h 8h 9h Jh Kh Ac Ad");
+        let full_house = short_deck("Ks Kc Kd Qh Qs 7c 6d");
+        let straight = short_deck("6c 7d 8h 9s Td Jc Kd");
+        let three_of_a_kind = short_deck("As Ac Ad 8h 9s Jc Qd");
+        assert!(four_of_a_kind > flush);
+        assert!(full_house > straight);
+        assert!(straight > three_of_a_kind);
+    }
+
+    #[test]
+    fn flushes_compare_by_cards_in_short_deck() {
+        let ace_high = short_deck("6h 8h 9h Jh Ah 7c 7d");
+        let king_high = short_deck("6h 8h 9h Jh Kh Ac Ad");
+        assert!(ace_high > king_high);
+        assert_eq!(
+            short_deck("6h 8h 9h Jh Ah 7c 7d"),
+            short_deck("6s 8s 9s Js As Tc Td")
+        );
+    }
+}
@@ -0,0 +1,250 @@
+use std::sync::OnceLock;
+
+use super::{
+    components::Card,
+    hand_evaluation::{self as he, Rank, Rankable},
+};
+
+const N_RANKS: usize = 13;
+const MIN_CARDS: usize = 5;
+const MAX_CARDS: usize = 7;
+
+/// Precomputed ranks of every hand of 5 to 7 cards, equal to those of [[Rankable::rank]] or
+/// [[Rankable::rank_short_deck]].
+///
+/// Without a flush, the rank depends only on how many cards of each rank the hand holds: the
+/// counts are perfectly hashed into a table. With a flush, neither quads nor a full house fit
+/// in 7 cards, so the rank depends only on the ranks of the flush suit.
+pub struct LookupEvaluator {
+    /// Hash contribution by rank, cards left to place on this rank and the ranks above, and
+    /// count of the rank
+    offsets: [[[u32; 5]; MAX_CARDS + 1]; N_RANKS],
+    /// By number of cards from [[MIN_CARDS]], then by hash of the rank counts
+    no_flush: Vec<Vec<Rank>>,
+    /// By bitset of the ranks in the flush suit
+    flush: Vec<Rank>,
+}
+
+impl LookupEvaluator {
+    pub fn holdem() -> &'static Self {
+        static HOLDEM: OnceLock<LookupEvaluator> = OnceLock::new();
+        HOLDEM.get_or_init(|| Self::new(|hand| hand.rank()))
+    }
+
+    /// Compare the results as [[he::ShortDeckRank]]
+    pub fn short_deck() -> &'static Self {
+        static SHORT_DECK: OnceLock<LookupEvaluator> = OnceLock::new();
+        SHORT_DECK.get_or_init(|| Self::new(|hand| hand.rank_short_deck()))
+    }
+
+    /// Fills the tables with `rank_cards` on one hand per entry
+    fn new(rank_cards: impl Fn(&Vec<he::Card>) -> Rank) -> Self {
+        // Ways to give `n` ranks from 0 to 4 cards each, `k` cards in all
+        let mut n_ways = [[0u32; MAX_CARDS + 1]; N_RANKS + 1];
+        n_ways[0][0] = 1;
+        for n in 1..=N_RANKS {
+            for k in 0..=MAX_CARDS {
+                n_ways[n][k] = (0..=k.min(4)).map(|count| n_ways[n - 1][k - count]).sum();
+            }
+        }
+        let mut offsets = [[[0u32; 5]; MAX_CARDS + 1]; N_RANKS];
+        for (rank, rank_offsets) in offsets.iter_mut().enumerate() {
+            let n_ranks_below = N_RANKS - rank - 1;
+            for (n_left, count_offsets) in rank_offsets.iter_mut().enumerate() {
+                for count in 1..5 {
+                    let lower_count = count - 1;
+                    count_offsets[count] = count_offsets[lower_count]
+                        + if lower_count <= n_left {
+                            n_ways[n_ranks_below][n_left - lower_count]
+                        } else {
+                            0
+                        };
+                }
+            }
+        }
+
+        let mut evaluator = Self {
+            offsets,
+            no_flush: Vec::new(),
+            flush: vec![Rank::HighCard(0); 1 << N_RANKS],
+        };
+        for n_cards in MIN_CARDS..=MAX_CARDS {
+            let mut table = vec![Rank::HighCard(0); n_ways[N_RANKS][n_cards] as usize];
+            for_each_rank_counts(&mut [0; N_RANKS], 0, n_cards, &mut |counts| {
+                let rank_set = (0..N_RANKS)
+                    .filter(|&rank| counts[rank] > 0)
+                    .fold(0, |rank_set, rank| rank_set | 1 << rank);
+                table[evaluator.hash(counts, rank_set, n_cards)] =
+                    rank_cards(&no_flush_hand(counts));
+            });
+            evaluator.no_flush.push(table);
+        }
+        for rank_set in 0..1usize << N_RANKS {
+            if (MIN_CARDS..=MAX_CARDS).contains(&(rank_set.count_ones() as usize)) {
+                let hand: Vec<he::Card> = (0..N_RANKS as u8)
+                    .filter(|rank| rank_set & 1 << rank != 0)
+                    .map(|rank| he::Card::new(rank * 4))
+                    .collect();
+                evaluator.flush[rank_set] = rank_cards(&hand);
+            }
+        }
+        evaluator
+    }
+
+    /// Best five-card hand of 5 to 7 cards
+    pub fn rank<'a>(&self, cards: impl IntoIterator<Item = &'a Card> + Clone) -> Rank {
+        let mut counts = [0u8; N_RANKS];
+        let mut rank_set = 0u32;
+        // Four bits per suit
+        let mut suit_counts = 0u32;
+        let mut n_cards = 0;
+        for card in cards.clone() {
+            counts[(card.0 >> 2) as usize] += 1;
+            rank_set |= 1 << (card.0 >> 2);
+            suit_counts += 1 << ((card.0 & 3) * 4);
+            n_cards += 1;
+        }
+        assert!(
+            (MIN_CARDS..=MAX_CARDS).contains(&n_cards),
+            "Ranks hands of 5 to 7 cards"
+        );
+        // Adding 3 sets the top bit of the suits of 5 cards or more
+        let flush_bits = (suit_counts + 0x3333) & 0x8888;
+        if flush_bits != 0 {
+            let flush_suit = (flush_bits.trailing_zeros() / 4) as u8;
+            let rank_set = cards
+                .into_iter()
+                .filter(|card| card.0 & 3 == flush_suit)
+                .fold(0, |rank_set, card| rank_set | 1 << (card.0 >> 2));
+            return self.flush[rank_set];
+        }
+        self.no_flush[n_cards - MIN_CARDS][self.hash(&counts, rank_set, n_cards)]
+    }
+
+    /// Index of `counts` among the rank counts of `n_cards` cards. Ranks held by no card,
+    /// out of `rank_set`, add nothing.
+    fn hash(&self, counts: &[u8; N_RANKS], rank_set: u32, n_cards: usize) -> usize {
+        let mut n_left = n_cards;
+        let mut hash = 0;
+        let mut rank_set = rank_set;
+        while rank_set != 0 {
+            let rank = rank_set.trailing_zeros() as usize;
+            let count = counts[rank] as usize;
+            hash += self.offsets[rank][n_left][count];
+            n_left -= count;
+            rank_set &= rank_set - 1;
+        }
+        hash as usize
+    }
+}
+
+/// Calls `f` with every way to hold `n_left` more cards on the ranks from `rank`
+fn for_each_rank_counts(
+    counts: &mut [u8; N_RANKS],
+    rank: usize,
+    n_left: usize,
+    f: &mut impl FnMut(&[u8; N_RANKS]),
+) {
+    if rank == N_RANKS {
+        if n_left == 0 {
+            f(counts);
+        }
+        return;
+    }
+    for count in 0..=n_left.min(4) {
+        counts[rank] = count as u8;
+        for_each_rank_counts(counts, rank + 1, n_left - count, f);
+    }
+    counts[rank] = 0;
+}
+
+/// Cards of the given rank counts, spread over the suits so that none makes a flush
+fn no_flush_hand(counts: &[u8; N_RANKS]) -> Vec<he::Card> {
+    let mut suit_counts = [0u8; 4];
+    let mut hand = Vec::new();
+    for (rank, &count) in counts.iter().enumerate() {
+        let mut suits = [0u8, 1, 2, 3];
+        suits.sort_by_key(|&suit| suit_counts[suit as usize]);
+        for &suit in suits.iter().take(count as usize) {
+            suit_counts[suit as usize] += 1;
+            hand.push(he::Card::new(rank as u8 * 4 + suit));
+        }
+    }
+    hand
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Checks every hand of `n_cards` from `deck`, the suits of each rank in any order
+    fn cross_check(
+        evaluator: &LookupEvaluator,
+        deck: &[u8],
+        n_cards: usize,
+        rank_cards: impl Fn(&Vec<he::Card>) -> Rank,
+    ) -> u64 {
+        let mut n_hands = 0;
+        let mut indices: Vec<usize> = (0..n_cards).collect();
+        loop {
+            let cards: Vec<Card> = indices.iter().map(|&i| Card(deck[i])).collect();
+            let hand: Vec<he::Card> = cards.iter().map(|card| he::Card::new(card.0)).collect();
+            assert_eq!(
+                evaluator.rank(&cards),
+                rank_cards(&hand),
+                "{:?}",
+                cards
+                    .iter()
+                    .map(|card| card.to_string())
+                    .collect::<Vec<_>>()
+            );
+            n_hands += 1;
+
+            // Next combination in lexicographic order
+            let Some(i) = (0..n_cards)
+                .rev()
+                .find(|&i| indices[i] < deck.len() - n_cards + i)
+            else {
+                return n_hands;
+            };
+            indices[i] += 1;
+            for j in i + 1..n_cards {
+                indices[j] = indices[j - 1] + 1;
+            }
+        }
+    }
+
+    #[test]
+    fn every_five_card_hand() {
+        let deck: Vec<u8> = (0..52).collect();
+        let evaluator = LookupEvaluator::holdem();
+        assert_eq!(
+            cross_check(evaluator, &deck, 5, |hand| hand.rank()),
+            2_598_960
+        );
+    }
+
+    #[test]
+    fn every_short_deck_hand() {
+        let deck: Vec<u8> = (16..52).collect();
+        let evaluator = LookupEvaluator::short_deck();
+        let rank_cards = |hand: &Vec<he::Card>| hand.rank_short_deck();
+        assert_eq!(cross_check(evaluator, &deck, 5, rank_cards), 376_992);
+        assert_eq!(cross_check(evaluator, &deck, 7, rank_cards), 8_347_680);
+    }
+
+    #[test]
+    #[ignore = "154 million hands, run with: cargo test --release -- --ignored"]
+    fn every_six_and_seven_card_hand() {
+        let deck: Vec<u8> = (0..52).collect();
+        let evaluator = LookupEvaluator::holdem();
+        assert_eq!(
+            cross_check(evaluator, &deck, 6, |hand| hand.rank()),
+            20_358_520
+        );
+        assert_eq!(
+            cross_check(evaluator, &deck, 7, |hand| hand.rank()),
+            133_784_560
+        );
+    }
+}
@@ -0,0 +1,157 @@
+use std::fmt;
+
+use super::{
+    components::Card,
+    hand_evaluation::{Rank, ShortDeckRank},
+    lookup_evaluation::LookupEvaluator,
+    resources::GameVariant,
+};
+
+const RANK_NAMES: [&str; 13] = [
+    "Two", "Three", "Four", "Five", "Six", "Seven", "Eight", "Nine", "Ten", "Jack", "Queen",
+    "King", "Ace",
+];
+
+/// Best five cards of a player and what they make
+#[derive(Debug, Clone)]
+pub struct MadeHand {
+    pub rank: Rank,
+    /// The cards making the hand first, then the kickers, each highest first. A straight
+    /// runs down from its top card, the ace of a wheel last.
+    pub cards: Vec<Card>,
+}
+
+impl MadeHand {
+    /// "Full house, Kings full of Sevens", "Flush, Ace high", ...
+    pub fn description(&self) -> String {
+        let name = |index: usize| RANK_NAMES[self.cards[index].rank() as usize];
+        let plural = |index: usize| match name(index) {
+            "Six" => "Sixes".to_string(),
+            name => format!("{}s", name),
+        };
+        match self.rank {
+            Rank::HighCard(_) => format!("High card, {}", name(0)),
+            Rank::OnePair(_) => format!("One pair, {}", plural(0)),
+            Rank::TwoPair(_) => format!("Two pair, {} and {}", plural(0), plural(2)),
+            Rank::ThreeOfAKind(_) => format!("Three of a kind, {}", plural(0)),
+            Rank::Straight(_) => format!("Straight, {} high", name(0)),
+            Rank::Flush(_) => format!("Flush, {} high", name(0)),
+            Rank::FullHouse(_) => format!("Full house, {} full of {}", plural(0), plural(3)),
+            Rank::FourOfAKind(_) => format!("Four of a kind, {}", plural(0)),
+            Rank::StraightFlush(_) if self.cards[0].rank() == 12 => "Royal flush".to_string(),
+            Rank::StraightFlush(_) => format!("Straight flush, {} high", name(0)),
+        }
+    }
+}
+
+/// "Kh Kd Ks 7c 7h: Full house, Kings full of Sevens"
+impl fmt::Display for MadeHand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let cards: Vec<String> = self.cards.iter().map(Card::to_string).collect();
+        write!(f, "{}: {}", cards.join(" "), self.description())
+    }
+}
+
+/// Best five-card hand from `hole_cards` and `board`, [[None]] with fewer than five cards.
+/// In Omaha the hand is made of exactly two hole cards and three board cards.
+pub fn best_hand(hole_cards: &[Card], board: &[Card], variant: GameVariant) -> Option<MadeHand> {
+    let candidates: Vec<Vec<Card>> = match variant {
+        GameVariant::Omaha => {
+            let mut candidates = Vec::new();
+            for hole_pair in subsets(hole_cards, 2) {
+                for board_triple in subsets(board, 3) {
+                    candidates.push([hole_pair.clone(), board_triple].concat());
+                }
+            }
+            candidates
+        }
+        GameVariant::Holdem | GameVariant::ShortDeck => subsets(&[hole_cards, board].concat(), 5),
+    };
+    let evaluator = match variant {
+        GameVariant::ShortDeck => LookupEvaluator::short_deck(),
+        GameVariant::Holdem | GameVariant::Omaha => LookupEvaluator::holdem(),
+    };
+    let ranked = candidates
+        .into_iter()
+        .map(|cards| (evaluator.rank(&cards), cards));
+    let (rank, cards) = match variant {
+        GameVariant::ShortDeck => ranked.max_by_key(|(rank, _)| ShortDeckRank(*rank)),
+        GameVariant::Holdem | GameVariant::Omaha => ranked.max_by_key(|(rank, _)| *rank),
+    }?;
+    Some(MadeHand {
+        rank,
+        cards: ordered(&rank, cards),
+    })
+}
+
+/// Every `k` cards out of `cards`
+fn subsets(cards: &[Card], k: usize) -> Vec<Vec<Card>> {
+    if k == 0 {
+        return vec![vec![]];
+    }
+    if cards.len() < k {
+        return vec![];
+    }
+    let (first, rest) = cards.split_first().unwrap();
+    let mut subsets: Vec<Vec<Card>> = subsets(rest, k - 1)
+        .into_iter()
+        .map(|subset| [vec![first.clone()], subset].concat())
+        .collect();
+    subsets.extend(self::subsets(rest, k));
+    subsets
+}
+
+/// Groups of equal ranks first, the largest first, then by rank
+fn ordered(rank: &Rank, mut cards: Vec<Card>) -> Vec<Card> {
+    let ranks: Vec<u8> = cards.iter().map(Card::rank).collect();
+    let count = |card: &Card| ranks.iter().filter(|&&rank| rank == card.rank()).count();
+    cards.sort_by_key(|card| std::cmp::Reverse((count(card), card.rank(), card.suit())));
+    // The wheel is the only straight ranked 0: its ace plays low
+    if matches!(rank, Rank::Straight(0) | Rank::StraightFlush(0)) {
+        cards.rotate_left(1);
+    }
+    cards
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::table::components::parse_cards;
+
+    fn holdem_hand(hole_cards: &str, board: &str) -> MadeHand {
+        let hole_cards = parse_cards(hole_cards).unwrap();
+        let board = parse_cards(board).unwrap();
+        best_hand(&hole_cards, &board, GameVariant::Holdem).unwrap()
+    }
+
+    #[test]
+    fn wheel_ace_plays_low() {
+        let made_hand = holdem_hand("Ah2c", "3d4s5h9cKd");
+        assert_eq!(made_hand.description(), "Straight, Five high");
+        assert_eq!(made_hand.to_string(), "5h 4s 3d 2c Ah: Straight, Five high");
+    }
+
+    #[test]
+    fn royal_flush() {
+        let made_hand = holdem_hand("AhKh", "QhJhTh2c3d");
+        assert_eq!(made_hand.description(), "Royal flush");
+        let made_hand = holdem_hand("9hKh", "QhJhTh2c3d");
+        assert_eq!(made_hand.description(), "Straight flush, King high");
+    }
+
+    #[test]
+    fn plural_ranks() {
+        let made_hand = holdem_hand("6h6c", "6d2s2c9hKd");
+        assert_eq!(made_hand.description(), "Full house, Sixes full of Twos");
+        let made_hand = holdem_hand("6h6c", "Jd2sJc9hKd");
+        assert_eq!(made_hand.description(), "Two pair, Jacks and Sixes");
+    }
+
+    #[test]
+    fn kickers_follow_the_hand() {
+        let made_hand = holdem_hand("AhKd", "As7c4d9hQs");
+        assert_eq!(made_hand.to_string(), "Ah As Kd Qs 9h: One pair, Aces");
+        let made_hand = holdem_hand("8h3d", "As7c4dTh2s");
+        assert_eq!(made_hand.to_string(), "As Th 8h 7c 4d: High card, Ace");
+    }
+}
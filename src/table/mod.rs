@@ -1,15 +1,32 @@
 mod compare_hands;
 mod deck;
 mod plugin;
-mod resources;
+mod pots;
 mod setup;
 mod update;
-mod hand_evaluation;
 
+pub mod actions;
 pub mod betting;
+pub mod board_texture;
+pub mod bot;
+pub mod cfr;
 pub mod components;
+pub mod draws;
+pub mod engine;
+pub mod equity;
 pub mod events;
+pub mod hand_evaluation;
+pub mod lookup_evaluation;
+pub mod made_hand;
+pub mod profile;
+pub mod push_fold;
+pub mod range;
+pub mod resources;
+pub mod scenario;
+pub mod simulation;
 pub mod states;
+pub mod strategy;
+pub mod tournament;
 
 pub use plugin::TablePlugin;
-pub use setup::setup_table;
+pub use setup::{setup_scenario_table, setup_table};
@@ -2,15 +2,14 @@ use bevy::prelude::*;
 
 use super::{
     events::{HeroMoved, TableUpdated},
-    resources::DeckResource,
-    setup::setup_table,
+    resources::{GameStateResource, ScenarioResource, SeatStrategies, TableConfig},
+    setup::{setup_scenario_table, setup_table},
     states::{HandoutState, PausedState},
-    update::{
-        attribute_winnings, deal_community_cards, do_showdown, make_move, on_showdown_made,
-        pool_moved_chips, start_new_handout,
-    },
+    update::{advance_handout, make_move, sync_table},
 };
 
+/// Thin adapter over the engine: feeds it actions and time, and mirrors its state into
+/// components
 pub struct TablePlugin;
 
 impl Plugin for TablePlugin {
@@ -21,23 +20,30 @@ impl Plugin for TablePlugin {
         app.add_event::<TableUpdated>();
         app.add_event::<HeroMoved>();
 
-        app.init_resource::<DeckResource>();
+        app.init_resource::<TableConfig>();
+        app.init_resource::<SeatStrategies>();
         app.init_resource::<Time>();
 
-        app.add_systems(Startup, setup_table);
+        app.add_systems(
+            Startup,
+            (
+                setup_table.run_if(not(resource_exists::<ScenarioResource>)),
+                setup_scenario_table.run_if(resource_exists::<ScenarioResource>),
+            ),
+        );
 
         app.add_systems(
             Update,
             (
-                make_move.run_if(in_state(HandoutState::ExpectingMove)),
-                pool_moved_chips.run_if(in_state(HandoutState::ExpectingPool)),
-                deal_community_cards.run_if(in_state(HandoutState::ExpectingDeal)),
-                do_showdown.run_if(in_state(HandoutState::ExpectingShowdown)),
-                on_showdown_made.run_if(in_state(HandoutState::ShowdownMade)),
-                attribute_winnings.run_if(in_state(HandoutState::ExpectingWinningsAttribution)),
-                start_new_handout.run_if(in_state(HandoutState::HandoutEnded)),
-            )
-                .in_set(TableUpdateSet),
+                (
+                    make_move.run_if(in_state(HandoutState::ExpectingMove)),
+                    advance_handout.run_if(not(in_state(HandoutState::ExpectingMove))),
+                )
+                    .in_set(TableUpdateSet),
+                sync_table
+                    .after(TableUpdateSet)
+                    .run_if(resource_changed::<GameStateResource>),
+            ),
         );
 
         app.configure_sets(
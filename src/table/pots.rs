@@ -0,0 +1,181 @@
+/// Main pot or a side pot
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SidePot {
+    pub amount: u32,
+    /// Indices of players who can win the pot
+    pub eligible: Vec<usize>,
+}
+
+/// Builds the main pot and side pots from what each player put in during the handout.
+///
+/// `contributions[i]` and `is_active[i]` describe player `i`. Folded players' chips stay in the
+/// pots, but folded players are never eligible. A layer covered by a single player (uncalled
+/// bet) becomes a pot of its own that returns to that player.
+pub fn build_pots(contributions: &[u32], is_active: &[bool]) -> Vec<SidePot> {
+    let mut levels: Vec<u32> = contributions
+        .iter()
+        .zip(is_active.iter())
+        .filter(|(_, &active)| active)
+        .map(|(&contribution, _)| contribution)
+        .filter(|&contribution| contribution > 0)
+        .collect();
+    levels.sort_unstable();
+    levels.dedup();
+
+    let mut pots: Vec<SidePot> = Vec::new();
+    let mut previous_level = 0;
+    for level in levels {
+        let amount: u32 = contributions
+            .iter()
+            .map(|&contribution| contribution.min(level) - contribution.min(previous_level))
+            .sum();
+        let eligible: Vec<usize> = (0..contributions.len())
+            .filter(|&i| is_active[i] && contributions[i] >= level)
+            .collect();
+        pots.push(SidePot { amount, eligible });
+        previous_level = level;
+    }
+
+    // Chips of folded players above the highest active contribution. Cannot happen in a
+    // well-formed handout, but chips must not vanish.
+    let leftover: u32 = contributions
+        .iter()
+        .map(|&contribution| contribution.saturating_sub(previous_level))
+        .sum();
+    if leftover > 0 {
+        match pots.last_mut() {
+            Some(pot) => pot.amount += leftover,
+            None => pots.push(SidePot {
+                amount: leftover,
+                eligible: (0..contributions.len()).collect(),
+            }),
+        }
+    }
+    pots
+}
+
+/// Splits every pot among its winners and returns the amount won by each player.
+///
+/// `find_winners` receives the eligible players of a pot and returns the ones holding the best
+/// hand, the pot split among all eligible players if none. Odd chips go to the winners in the
+/// order they are returned.
+pub fn settle_pots<F>(pots: &[SidePot], n_players: usize, mut find_winners: F) -> Vec<u32>
+where
+    F: FnMut(&[usize]) -> Vec<usize>,
+{
+    let mut winnings = vec![0; n_players];
+    for pot in pots {
+        let winners = if pot.eligible.len() == 1 {
+            pot.eligible.clone()
+        } else {
+            find_winners(&pot.eligible)
+        };
+        // Chips must not vanish when no hand can be ranked
+        let winners = if winners.is_empty() {
+            pot.eligible.clone()
+        } else {
+            winners
+        };
+        let share = pot.amount / winners.len() as u32;
+        let odd_chips = (pot.amount % winners.len() as u32) as usize;
+        for (i, &winner) in winners.iter().enumerate() {
+            winnings[winner] += share + if i < odd_chips { 1 } else { 0 };
+        }
+    }
+    winnings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn total(pots: &[SidePot]) -> u32 {
+        pots.iter().map(|pot| pot.amount).sum()
+    }
+
+    #[test]
+    fn layered_all_ins() {
+        // Three all-ins of different sizes and a player covering them all
+        let contributions = [50, 100, 200, 300];
+        let pots = build_pots(&contributions, &[true; 4]);
+        assert_eq!(
+            pots,
+            vec![
+                SidePot {
+                    amount: 200,
+                    eligible: vec![0, 1, 2, 3],
+                },
+                SidePot {
+                    amount: 150,
+                    eligible: vec![1, 2, 3],
+                },
+                SidePot {
+                    amount: 200,
+                    eligible: vec![2, 3],
+                },
+                SidePot {
+                    amount: 100,
+                    eligible: vec![3],
+                },
+            ]
+        );
+        assert_eq!(total(&pots), contributions.iter().sum::<u32>());
+
+        // The shortest stack has the best hand, then the second shortest
+        let winnings = settle_pots(&pots, 4, |eligible| vec![eligible[0]]);
+        assert_eq!(winnings, vec![200, 150, 200, 100]);
+        assert_eq!(
+            winnings.iter().sum::<u32>(),
+            contributions.iter().sum::<u32>()
+        );
+    }
+
+    #[test]
+    fn uncalled_bet_returned() {
+        let contributions = [40, 100];
+        let pots = build_pots(&contributions, &[true, true]);
+        assert_eq!(pots.last().unwrap().eligible, vec![1]);
+        assert_eq!(pots.last().unwrap().amount, 60);
+
+        let winnings = settle_pots(&pots, 2, |_| vec![0]);
+        assert_eq!(winnings, vec![80, 60]);
+    }
+
+    #[test]
+    fn folded_contributions_stay_in_the_pots() {
+        // Player 1 folded after putting in more than the short all-in
+        let contributions = [30, 80, 120, 120];
+        let is_active = [true, false, true, true];
+        let pots = build_pots(&contributions, &is_active);
+        assert_eq!(pots[0].amount, 120);
+        assert_eq!(pots[0].eligible, vec![0, 2, 3]);
+        assert_eq!(pots[1].amount, 230);
+        assert_eq!(pots[1].eligible, vec![2, 3]);
+        assert_eq!(total(&pots), contributions.iter().sum::<u32>());
+
+        let winnings = settle_pots(&pots, 4, |eligible| vec![*eligible.last().unwrap()]);
+        assert_eq!(winnings, vec![0, 0, 0, 350]);
+    }
+
+    #[test]
+    fn odd_chips_on_split_pots() {
+        let contributions = [20, 20, 41, 60];
+        let is_active = [false, true, true, true];
+        let pots = build_pots(&contributions, &is_active);
+        assert_eq!(total(&pots), 141);
+
+        let winnings = settle_pots(&pots, 4, |eligible| eligible.to_vec());
+        assert_eq!(winnings.iter().sum::<u32>(), 141);
+        // 80 chips three ways, the odd chips to the first winners, then 42 between two, then
+        // 19 back to the last player
+        assert_eq!(winnings, vec![0, 27, 48, 66]);
+    }
+
+    #[test]
+    fn pot_without_winners_split_among_eligible() {
+        let contributions = [50, 50, 20];
+        let pots = build_pots(&contributions, &[true, true, false]);
+        let winnings = settle_pots(&pots, 3, |_| vec![]);
+        assert_eq!(winnings, vec![60, 60, 0]);
+    }
+}
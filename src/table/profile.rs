@@ -0,0 +1,316 @@
+use rand::Rng;
+use rand_chacha::ChaCha8Rng;
+
+use super::{
+    actions::Action,
+    components::{Card, Chips},
+    draws::{analyze_draws, board_category, category},
+    made_hand::best_hand,
+    range::{Combo, Range},
+    resources::GameVariant,
+    strategy::{GameView, Strategy},
+};
+
+/// Raises a street may see between rule-based bots before they only call
+const MAX_RAISES_PER_STREET: usize = 3;
+
+/// How good a hand is postflop, compared with what the board gives everyone
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum HandStrength {
+    /// Nothing beyond the board and no strong draw
+    Weak,
+    /// Flush draw or open-ended straight draw
+    Draw,
+    /// A pair made with a hole card, or one kind of hand better than the board
+    Medium,
+    /// Two pair or better made with the hole cards
+    Strong,
+}
+
+/// Strength of `hole_cards` on a flop, turn or river `board`
+pub fn hand_strength(hole_cards: &[Card], board: &[Card], variant: GameVariant) -> HandStrength {
+    let Some(made_hand) = best_hand(hole_cards, board, variant) else {
+        return HandStrength::Weak;
+    };
+    let made = category(&made_hand.rank, variant);
+    let board_made = board_category(board, variant);
+    let is_drawing = analyze_draws(hole_cards, board, variant)
+        .is_some_and(|draws| draws.flush_draw || draws.open_ended_straight_draw);
+    if made > board_made + 1 || (made >= 4 && made > board_made) {
+        HandStrength::Strong
+    } else if made > board_made {
+        HandStrength::Medium
+    } else if is_drawing {
+        HandStrength::Draw
+    } else {
+        HandStrength::Weak
+    }
+}
+
+/// Recognizable playing style of a rule-based bot
+#[derive(Debug, Clone)]
+pub struct Profile {
+    pub name: String,
+    /// Hands raised first in, by [[super::components::PreflopPosition]]: the big blind when
+    /// limped to, the small blind, the button, and so on. Positions past the end play the
+    /// last range.
+    pub opening_ranges: Vec<Range>,
+    /// Hands reraising a single raise, and calling a reraise
+    pub three_bet_range: Range,
+    /// Hands calling a single raise
+    pub calling_range: Range,
+    /// Hands calling the big blind when nobody has raised
+    pub limping_range: Range,
+    /// Opening raise in big blinds
+    pub open_raise_bb: f32,
+    /// Probability of betting the flop after raising preflop
+    pub cbet_frequency: f32,
+    /// Probability of betting or raising postflop, by [[HandStrength]]
+    pub aggression: [f32; 4],
+    /// Probability of calling a postflop bet rather than folding, by [[HandStrength]]
+    pub calling: [f32; 4],
+    /// Postflop bets and raises as a fraction of the pot after calling
+    pub bet_pot_fraction: f32,
+}
+
+impl Profile {
+    /// Tight and passive: few hands, bets only strong ones
+    pub fn nit() -> Self {
+        Self {
+            name: "nit".to_string(),
+            opening_ranges: ranges(&[
+                "TT+, AQs+, AKo",
+                "77+, ATs+, KQs, AJo+",
+                "66+, A9s+, KTs+, QJs, ATo+, KQo",
+                "77+, ATs+, KJs+, AJo+, KQo",
+                "88+, AJs+, KQs, AQo+",
+                "99+, AQs+, AKo",
+            ]),
+            three_bet_range: range("QQ+, AKs, AKo"),
+            calling_range: range("77-JJ, AJs-AQs, KQs, AQo"),
+            limping_range: Range::default(),
+            open_raise_bb: 3.0,
+            cbet_frequency: 0.5,
+            aggression: [0.05, 0.15, 0.35, 0.8],
+            calling: [0.05, 0.35, 0.5, 1.0],
+            bet_pot_fraction: 0.5,
+        }
+    }
+
+    /// Tight and aggressive: a solid regular
+    pub fn tag() -> Self {
+        Self {
+            name: "TAG".to_string(),
+            opening_ranges: ranges(&[
+                "99+, AJs+, KQs, AQo+",
+                "22+, A2s+, K8s+, Q9s+, J9s+, T9s, 98s, A9o+, KTo+, QJo",
+                "22+, A2s+, K5s+, Q8s+, J8s+, T8s+, 97s+, 86s+, 76s, 65s, A7o+, KTo+, QTo+, JTo",
+                "22+, A2s+, K9s+, Q9s+, J9s+, T9s, 98s, ATo+, KJo+",
+                "55+, A9s+, KTs+, QTs+, JTs, AJo+, KQo",
+                "77+, ATs+, KQs, AQo+",
+            ]),
+            three_bet_range: range("TT+, AQs+, AKo, A4s-A5s"),
+            calling_range: range("22-99, ATs-AJs, KJs+, QJs, JTs, T9s, AJo-AQo, KQo"),
+            limping_range: Range::default(),
+            open_raise_bb: 2.5,
+            cbet_frequency: 0.7,
+            aggression: [0.2, 0.5, 0.6, 0.9],
+            calling: [0.1, 0.6, 0.7, 1.0],
+            bet_pot_fraction: 0.66,
+        }
+    }
+
+    /// Loose and aggressive: many hands, many bets
+    pub fn lag() -> Self {
+        Self {
+            name: "LAG".to_string(),
+            opening_ranges: ranges(&[
+                "77+, A9s+, KTs+, QJs, AJo+, KQo",
+                "22+, A2s+, K2s+, Q5s+, J7s+, T7s+, 96s+, 85s+, 75s+, 64s+, 54s, A2o+, K8o+, \
+                 Q9o+, J9o+, T9o",
+                "22+, A2s+, K2s+, Q2s+, J5s+, T6s+, 96s+, 85s+, 75s+, 64s+, 54s, 43s, A2o+, \
+                 K5o+, Q8o+, J8o+, T8o+, 98o",
+                "22+, A2s+, K6s+, Q8s+, J8s+, T8s+, 97s+, 86s+, 75s+, 65s, 54s, A8o+, KTo+, \
+                 QTo+, JTo",
+                "22+, A2s+, K9s+, Q9s+, J9s+, T9s, 98s, 87s, ATo+, KJo+",
+                "55+, A9s+, KTs+, QTs+, JTs, T9s, AJo+, KQo",
+            ]),
+            three_bet_range: range("99+, AJs+, KQs, AQo+, A2s-A5s, K9s, 76s, 65s"),
+            calling_range: range(
+                "22-88, A6s-ATs, KTs-KJs, QTs+, J9s+, T9s, 98s, 87s, ATo-AJo, KQo",
+            ),
+            limping_range: Range::default(),
+            open_raise_bb: 2.5,
+            cbet_frequency: 0.85,
+            aggression: [0.35, 0.7, 0.7, 0.95],
+            calling: [0.2, 0.7, 0.8, 1.0],
+            bet_pot_fraction: 0.75,
+        }
+    }
+
+    /// Loose and passive: limps and calls almost anything, raises only the best hands
+    pub fn calling_station() -> Self {
+        let calling_range = range(
+            "22+, A2s+, K2s+, Q4s+, J6s+, T6s+, 96s+, 85s+, 75s+, 64s+, 54s, A2o+, K7o+, Q8o+, \
+             J8o+, T8o+, 98o",
+        );
+        Self {
+            name: "calling station".to_string(),
+            opening_ranges: ranges(&["JJ+, AKs, AKo"]),
+            three_bet_range: range("KK+"),
+            limping_range: calling_range.clone(),
+            calling_range,
+            open_raise_bb: 3.0,
+            cbet_frequency: 0.2,
+            aggression: [0.02, 0.05, 0.1, 0.4],
+            calling: [0.6, 0.9, 0.95, 1.0],
+            bet_pot_fraction: 0.5,
+        }
+    }
+
+    /// One of each profile
+    pub fn all() -> Vec<Self> {
+        vec![
+            Self::tag(),
+            Self::nit(),
+            Self::lag(),
+            Self::calling_station(),
+        ]
+    }
+}
+
+fn range(text: &str) -> Range {
+    text.parse()
+        .unwrap_or_else(|error| panic!("Invalid profile range {}: {}", text, error))
+}
+
+fn ranges(texts: &[&str]) -> Vec<Range> {
+    texts.iter().map(|text| range(text)).collect()
+}
+
+/// Plays by the rules of its [[Profile]]
+#[derive(Debug, Clone)]
+pub struct ProfileStrategy {
+    pub profile: Profile,
+}
+
+impl Strategy for ProfileStrategy {
+    fn decide(&mut self, view: &GameView, rng: &mut ChaCha8Rng) -> Action {
+        if view.street() == 0 {
+            self.decide_preflop(view, rng)
+        } else {
+            self.decide_postflop(view, rng)
+        }
+    }
+}
+
+impl ProfileStrategy {
+    fn decide_preflop(&self, view: &GameView, rng: &mut ChaCha8Rng) -> Action {
+        let profile = &self.profile;
+        let betting = view.betting();
+        let position = view.seat().preflop_position as usize;
+        let opening_range = &profile.opening_ranges[position.min(profile.opening_ranges.len() - 1)];
+        let mut in_range = |range: &Range| plays(range, view.hole_cards(), rng);
+        match n_raises(view, 0) {
+            0 if in_range(opening_range) => {
+                let raise_to = view.config().big_blind.0 as f32 * profile.open_raise_bb;
+                raise_or_call(view, raise_to as u32)
+            }
+            0 if view.to_call().0 == 0 => Action::Check,
+            0 if in_range(&profile.limping_range) => call(view),
+            1 if in_range(&profile.three_bet_range) => raise_or_call(view, 3 * betting.max_front()),
+            1 if in_range(&profile.calling_range) => call(view),
+            2.. if in_range(&profile.three_bet_range) => call(view),
+            _ => check_or_fold(view),
+        }
+    }
+
+    fn decide_postflop(&self, view: &GameView, rng: &mut ChaCha8Rng) -> Action {
+        let profile = &self.profile;
+        let strength = hand_strength(view.hole_cards(), view.board(), view.config().variant);
+        let aggression = profile.aggression[strength as usize];
+        // Continuation bet: first in on the flop after the last raise preflop
+        let is_preflop_aggressor = view
+            .history()
+            .iter()
+            .rev()
+            .find(|record| record.street == 0 && is_raise(&record.action))
+            .is_some_and(|record| record.seat_index == view.seat_index());
+        let bet_frequency = if view.street() == 1 && is_preflop_aggressor {
+            aggression.max(profile.cbet_frequency)
+        } else {
+            aggression
+        };
+        let raise_to = view.betting().pot_raise_to(profile.bet_pot_fraction);
+        if view.to_call().0 == 0 {
+            if rng.gen::<f32>() < bet_frequency {
+                raise_or_call(view, raise_to)
+            } else {
+                Action::Check
+            }
+        } else if n_raises(view, view.street()) < MAX_RAISES_PER_STREET
+            && rng.gen::<f32>() < aggression
+        {
+            raise_or_call(view, raise_to)
+        } else if rng.gen::<f32>() < profile.calling[strength as usize] {
+            call(view)
+        } else {
+            Action::Fold
+        }
+    }
+}
+
+/// Whether to play a hand of `range`, as often as its weight. In Omaha, the best weight of
+/// any two hole cards.
+fn plays(range: &Range, hole_cards: &[Card], rng: &mut ChaCha8Rng) -> bool {
+    let mut weight: f32 = 0.0;
+    for (i, first) in hole_cards.iter().enumerate() {
+        for second in hole_cards[i + 1..].iter() {
+            if let Some(combo) = Combo::new(first, second) {
+                weight = weight.max(range.weight(combo));
+            }
+        }
+    }
+    weight > 0.0 && rng.gen::<f32>() < weight
+}
+
+fn is_raise(action: &Action) -> bool {
+    matches!(action, Action::Bet(_) | Action::Raise(_) | Action::AllIn(_))
+}
+
+/// Bets and raises on `street` so far, all-ins counted as raises
+fn n_raises(view: &GameView, street: u8) -> usize {
+    view.history()
+        .iter()
+        .filter(|record| record.street == street && is_raise(&record.action))
+        .count()
+}
+
+/// Bet or raise to about `raise_to`, or call when raising is not allowed
+fn raise_or_call(view: &GameView, raise_to: u32) -> Action {
+    let legal_actions = view.legal_actions();
+    match legal_actions.raise {
+        Some(raise) => {
+            legal_actions.raise_action(Chips(raise_to.clamp(raise.min_to.0, raise.max_to.0)))
+        }
+        None => call(view),
+    }
+}
+
+/// Call, all-in if short, or check when there is nothing to call
+fn call(view: &GameView) -> Action {
+    let options = view.legal_actions().options();
+    if options[0] == Action::Check {
+        Action::Check
+    } else {
+        options[1]
+    }
+}
+
+fn check_or_fold(view: &GameView) -> Action {
+    if view.to_call().0 == 0 {
+        Action::Check
+    } else {
+        Action::Fold
+    }
+}
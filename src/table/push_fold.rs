@@ -0,0 +1,443 @@
+use std::sync::Arc;
+
+use rand::{seq::SliceRandom, Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use super::{
+    actions::Action,
+    cfr::{normalized, BettingGame, CfrSolver, PreflopEquities, StrategyTable},
+    components::Chips,
+    engine::GameState,
+    range::{class_combos, Combo, Range, N_HAND_CLASSES},
+    resources::TableConfig,
+    scenario::Scenario,
+    strategy::{GameView, Strategy},
+};
+
+/// Effective stacks to chart when none are given
+pub const DEFAULT_CHART_STACKS_BB: [f64; 10] =
+    [2.0, 3.0, 4.0, 5.0, 6.0, 8.0, 10.0, 12.0, 15.0, 20.0];
+
+/// Nodes of the small blind's and the big blind's decisions in [[BettingGame::push_fold]]
+const PUSH_NODE: usize = 0;
+const CALL_NODE: usize = 2;
+
+/// Heads-up push/fold ranges at one stack depth
+#[derive(Debug, Clone)]
+pub struct PushFoldChart {
+    /// Effective stack in big blinds
+    pub stack_bb: f64,
+    /// Hands the small blind shoves, weighted by how often
+    pub push: Range,
+    /// Hands the big blind calls a shove with
+    pub call: Range,
+}
+
+impl PushFoldChart {
+    /// Approximate equilibrium after `n_iterations` of CFR+
+    pub fn solve(equities: &PreflopEquities, stack_bb: f64, n_iterations: u32) -> Self {
+        let game = BettingGame::push_fold(equities, stack_bb);
+        let mut solver = CfrSolver::new(&game);
+        solver.run(n_iterations);
+        Self::from_table(stack_bb, &solver.average_strategy())
+    }
+
+    /// Ranges of a [[BettingGame::push_fold]] strategy
+    pub fn from_table(stack_bb: f64, table: &StrategyTable) -> Self {
+        let range = |node: usize| {
+            let weights: Vec<f64> = (0..N_HAND_CLASSES)
+                .map(|class| table.action_probabilities(node, class)[1])
+                .collect();
+            class_range(&weights)
+        };
+        Self {
+            stack_bb,
+            push: range(PUSH_NODE),
+            call: range(CALL_NODE),
+        }
+    }
+
+    /// How `strategy` plays heads-up at `stack_bb`, first in from the small blind and facing a
+    /// shove in the big blind, sampled on `n_deals` hands of every kind. Any bet, raise or call
+    /// of the small blind counts as a shove, any call or raise of the big blind as a call.
+    /// With [[Self::to_table]], measures the exploitability of any bot. Stacks must be deeper
+    /// than the big blind.
+    pub fn from_strategy(
+        strategy: &mut dyn Strategy,
+        stack_bb: f64,
+        n_deals: u32,
+        seed: u64,
+    ) -> Self {
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        let config = TableConfig {
+            n_seats: 2,
+            ..Default::default()
+        };
+        let names = ["Small blind".to_string(), "Big blind".to_string()];
+        let stack = Chips((stack_bb * config.big_blind.0 as f64).round() as u32);
+        let mut push = vec![0.0; N_HAND_CLASSES];
+        let mut call = vec![0.0; N_HAND_CLASSES];
+        for class in 0..N_HAND_CLASSES {
+            let combos = class_combos(class);
+            for _ in 0..n_deals {
+                let combo = combos.choose(&mut rng).unwrap();
+                for (seat_index, frequencies) in [(0, &mut push), (1, &mut call)] {
+                    // The button, seat 0, is the small blind
+                    let scenario = Scenario {
+                        n_seats: Some(2),
+                        button_seat: Some(0),
+                        stacks: vec![(0, stack), (1, stack)],
+                        hole_cards: vec![(seat_index as u8, combo.cards().to_vec())],
+                        ..Default::default()
+                    };
+                    let config = TableConfig {
+                        seed: Some(rng.gen()),
+                        ..config.clone()
+                    };
+                    let mut state = GameState::from_scenario(config, &names, &scenario)
+                        .expect("Heads-up scenario with valid stacks");
+                    if seat_index == 1 {
+                        let legal_actions = state.legal_actions().unwrap();
+                        let raise = legal_actions
+                            .raise
+                            .expect("Stacks deeper than the big blind");
+                        let shove = legal_actions.raise_action(raise.max_to);
+                        state.apply_action(shove).unwrap();
+                    }
+                    let action = strategy.decide(&GameView::new(&state, seat_index), &mut rng);
+                    if !matches!(action, Action::Fold | Action::Check) {
+                        frequencies[class] += 1.0 / n_deals as f64;
+                    }
+                }
+            }
+        }
+        Self {
+            stack_bb,
+            push: class_range(&push),
+            call: class_range(&call),
+        }
+    }
+
+    /// Strategy of the chart in [[BettingGame::push_fold]], to measure its exploitability
+    pub fn to_table(&self, game: &BettingGame) -> StrategyTable {
+        let mut table = StrategyTable::uniform(game);
+        for (node, range) in [(PUSH_NODE, &self.push), (CALL_NODE, &self.call)] {
+            for class in 0..N_HAND_CLASSES {
+                let combos = class_combos(class);
+                let weight = combos
+                    .iter()
+                    .map(|combo| range.weight(*combo) as f64)
+                    .sum::<f64>()
+                    / combos.len() as f64;
+                table.probabilities[node][class] = vec![1.0 - weight, weight];
+            }
+        }
+        table
+    }
+}
+
+/// Pushes or folds by a [[PushFoldChart]]: shoves the hands of the push range first in, calls
+/// a raise with the call range
+#[derive(Debug, Clone)]
+pub struct PushFoldStrategy {
+    pub chart: PushFoldChart,
+}
+
+impl Strategy for PushFoldStrategy {
+    fn decide(&mut self, view: &GameView, rng: &mut ChaCha8Rng) -> Action {
+        let legal_actions = view.legal_actions();
+        let is_raised = view.history().iter().any(|record| {
+            record.street == 0
+                && matches!(
+                    record.action,
+                    Action::Bet(_) | Action::Raise(_) | Action::AllIn(_)
+                )
+        });
+        let range = if is_raised {
+            &self.chart.call
+        } else {
+            &self.chart.push
+        };
+        let weight = match view.hole_cards() {
+            [first, second] => Combo::new(first, second).map_or(0.0, |combo| range.weight(combo)),
+            _ => 0.0,
+        };
+        let plays = view.street() == 0 && rng.gen::<f32>() < weight;
+        match (plays, legal_actions.raise) {
+            (true, Some(raise)) if !is_raised => legal_actions.raise_action(raise.max_to),
+            _ if legal_actions.can_check() => Action::Check,
+            // Call, all-in if short
+            (true, _) => legal_actions.options()[1],
+            (false, _) => Action::Fold,
+        }
+    }
+}
+
+/// Nash push/fold ranges of every position at one effective stack, when the first player in
+/// shoves or folds and the others call or fold. A call ends the action: all-ins are
+/// heads-up. Positions only depend on the players after them, so the chart of a table also
+/// fits it when fewer are dealt in.
+#[derive(Debug, Clone)]
+pub struct NashChart {
+    /// Effective stack in big blinds
+    pub stack_bb: f64,
+    /// Hands shoved first in, by [[super::components::PreflopPosition]]. None for the big
+    /// blind.
+    pub push: Vec<Range>,
+    /// Hands calling a shove, by position of the caller, then of the player who shoved. None
+    /// when the caller acts first.
+    pub call: Vec<Vec<Range>>,
+}
+
+impl NashChart {
+    /// Approximate equilibrium of the blinds and antes of `config`, without a straddle, after
+    /// `n_iterations` of fictitious play: every player takes the best response to the average
+    /// strategies so far, the later iterations weighing more
+    pub fn solve(
+        equities: &PreflopEquities,
+        config: &TableConfig,
+        stack_bb: f64,
+        n_iterations: u32,
+    ) -> Self {
+        let n_players = config.n_seats as usize;
+        let big_blind = config.big_blind.0 as f64;
+        let posted: Vec<f64> = (0..n_players)
+            .map(|position| {
+                let (blind, ante) = config.forced_bets(position as u8, n_players as u8);
+                let blind = if position < 2 { blind.0 } else { 0 };
+                ((blind + ante.0) as f64 / big_blind).min(stack_bb)
+            })
+            .collect();
+        let dead_money: f64 = posted.iter().sum();
+        // Hands of an opponent, given one's own, not minding the cards of those who folded
+        let opponent_weights: Vec<Vec<f64>> = equities
+            .deal_weights
+            .iter()
+            .map(|weights| normalized(weights))
+            .collect();
+        let equity = &equities.equities;
+        // Pot of an all-in between the shover and the caller
+        let all_in_pot = |pusher: usize, caller: usize| {
+            dead_money - posted[pusher] - posted[caller] + 2.0 * stack_bb
+        };
+
+        let mut push = vec![vec![0.5; N_HAND_CLASSES]; n_players];
+        push[0] = vec![0.0; N_HAND_CLASSES];
+        let mut call = vec![vec![vec![0.5; N_HAND_CLASSES]; n_players]; n_players];
+        for iteration in 0..n_iterations {
+            let step = 2.0 / (iteration + 2) as f64;
+            let mut best_push = vec![vec![0.0; N_HAND_CLASSES]; n_players];
+            for (pusher, best_push) in best_push.iter_mut().enumerate().skip(1) {
+                for (class, best_push) in best_push.iter_mut().enumerate() {
+                    // Later positions act first; the shove takes the pot if all of them fold
+                    let mut value = 0.0;
+                    let mut all_fold = 1.0;
+                    for caller in (0..pusher).rev() {
+                        let (mut called, mut won) = (0.0, 0.0);
+                        for (other, weight) in opponent_weights[class].iter().enumerate() {
+                            let calls = weight * call[caller][pusher][other];
+                            called += calls;
+                            won += calls * equity[class][other];
+                        }
+                        value += all_fold
+                            * (won * all_in_pot(pusher, caller)
+                                - called * (stack_bb - posted[pusher]));
+                        all_fold *= 1.0 - called;
+                    }
+                    value += all_fold * dead_money;
+                    *best_push = if value > 0.0 { 1.0 } else { 0.0 };
+                }
+            }
+            let mut best_call = vec![vec![vec![0.0; N_HAND_CLASSES]; n_players]; n_players];
+            for (caller, best_call) in best_call.iter_mut().enumerate() {
+                for (pusher, best_call) in best_call.iter_mut().enumerate().skip(caller + 1) {
+                    let pot = all_in_pot(pusher, caller);
+                    for (class, best_call) in best_call.iter_mut().enumerate() {
+                        // Folding loses nothing more than the chips already posted
+                        let value: f64 = opponent_weights[class]
+                            .iter()
+                            .zip(push[pusher].iter())
+                            .zip(equity[class].iter())
+                            .map(|((weight, pushes), equity)| {
+                                weight * pushes * (equity * pot - (stack_bb - posted[caller]))
+                            })
+                            .sum();
+                        *best_call = if value > 0.0 { 1.0 } else { 0.0 };
+                    }
+                }
+            }
+            for (average, best) in push.iter_mut().flatten().zip(best_push.iter().flatten()) {
+                *average += step * (best - *average);
+            }
+            for (average, best) in call
+                .iter_mut()
+                .flatten()
+                .flatten()
+                .zip(best_call.iter().flatten().flatten())
+            {
+                *average += step * (best - *average);
+            }
+        }
+
+        Self {
+            stack_bb,
+            push: push.iter().map(|weights| class_range(weights)).collect(),
+            call: call
+                .iter()
+                .enumerate()
+                .map(|(caller, by_pusher)| {
+                    by_pusher
+                        .iter()
+                        .enumerate()
+                        .map(|(pusher, weights)| {
+                            if pusher > caller {
+                                class_range(weights)
+                            } else {
+                                Range::default()
+                            }
+                        })
+                        .collect()
+                })
+                .collect(),
+        }
+    }
+}
+
+/// [[NashChart]]s of a table at several effective stacks
+#[derive(Debug, Clone, Default)]
+pub struct NashCharts {
+    /// By increasing stack
+    pub charts: Vec<NashChart>,
+}
+
+impl NashCharts {
+    /// One [[NashChart]] for each of `stacks_bb`
+    pub fn generate(
+        equities: &PreflopEquities,
+        config: &TableConfig,
+        stacks_bb: &[f64],
+        n_iterations: u32,
+    ) -> Self {
+        let mut charts: Vec<NashChart> = stacks_bb
+            .iter()
+            .map(|stack_bb| NashChart::solve(equities, config, *stack_bb, n_iterations))
+            .collect();
+        charts.sort_by(|a, b| a.stack_bb.total_cmp(&b.stack_bb));
+        Self { charts }
+    }
+
+    /// Chart of the stack closest to `stack_bb`
+    pub fn chart(&self, stack_bb: f64) -> Option<&NashChart> {
+        self.charts.iter().min_by(|a, b| {
+            (a.stack_bb - stack_bb)
+                .abs()
+                .total_cmp(&(b.stack_bb - stack_bb).abs())
+        })
+    }
+}
+
+/// Pushes or folds by [[NashCharts]] once the effective stack is short, and plays `deep`
+/// otherwise and after the flop. Hold'em no-limit only.
+pub struct ShortStackStrategy {
+    /// Shared by the bots of a table
+    pub charts: Arc<NashCharts>,
+    /// Effective stack in big blinds at or below which it pushes or folds
+    pub threshold_bb: f64,
+    pub deep: Box<dyn Strategy>,
+}
+
+impl Strategy for ShortStackStrategy {
+    fn decide(&mut self, view: &GameView, rng: &mut ChaCha8Rng) -> Action {
+        let stack_bb = effective_stack(view) as f64 / view.config().big_blind.0 as f64;
+        let chart = self.charts.chart(stack_bb);
+        match (view.street(), view.hole_cards(), chart) {
+            (0, [first, second], Some(chart)) if stack_bb <= self.threshold_bb => {
+                let weight = chart_range(view, chart)
+                    .zip(Combo::new(first, second))
+                    .map_or(0.0, |(range, combo)| range.weight(combo));
+                push_or_fold(view, rng.gen::<f32>() < weight)
+            }
+            _ => self.deep.decide(view, rng),
+        }
+    }
+}
+
+/// Smaller of one's stack and the biggest stack of the others still in, fronts included
+fn effective_stack(view: &GameView) -> u32 {
+    let seats = view.seats();
+    let own = view.seat().stack.0 + view.seat().front.0;
+    let biggest_other = seats
+        .iter()
+        .enumerate()
+        .filter(|(seat_index, seat)| *seat_index != view.seat_index() && seat.is_active)
+        .map(|(_, seat)| seat.stack.0 + seat.front.0)
+        .max()
+        .unwrap_or(own);
+    own.min(biggest_other)
+}
+
+/// Push range first in, or the range calling the last player who raised
+fn chart_range<'a>(view: &GameView, chart: &'a NashChart) -> Option<&'a Range> {
+    let position = view.seat().preflop_position as usize;
+    let last_raise = view.history().iter().rev().find(|record| {
+        record.street == 0
+            && matches!(
+                record.action,
+                Action::Bet(_) | Action::Raise(_) | Action::AllIn(_)
+            )
+    });
+    match last_raise {
+        Some(record) => {
+            let pusher = view.seats()[record.seat_index].preflop_position as usize;
+            chart.call.get(position)?.get(pusher)
+        }
+        None => chart.push.get(position),
+    }
+}
+
+/// All-in, or call when a raise is not allowed, or check or fold
+fn push_or_fold(view: &GameView, plays: bool) -> Action {
+    let legal_actions = view.legal_actions();
+    match (plays, legal_actions.raise) {
+        (true, Some(raise)) => legal_actions.raise_action(raise.max_to),
+        _ if legal_actions.can_check() => Action::Check,
+        // Call, all-in if short
+        (true, None) => legal_actions.options()[1],
+        (false, _) => Action::Fold,
+    }
+}
+
+/// Range of the weights of each kind of starting hand, rounded to a percent to drop what is
+/// left of a solver's noise
+fn class_range(weights: &[f64]) -> Range {
+    let mut range = Range::default();
+    for (class, weight) in weights.iter().enumerate() {
+        let weight = ((weight * 100.0).round() / 100.0) as f32;
+        for combo in class_combos(class) {
+            range.set_weight(combo, weight);
+        }
+    }
+    range
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::table::bot::RandomStrategy;
+
+    #[test]
+    fn exploitability_of_bots() {
+        let equities = PreflopEquities::estimate(10, 1);
+        let game = BettingGame::push_fold(&equities, 10.0);
+        let random = PushFoldChart::from_strategy(&mut RandomStrategy, 10.0, 4, 1);
+        assert!(game.exploitability(&random.to_table(&game)) > 0.3);
+
+        let solved = PushFoldChart::solve(&equities, 10.0, 200);
+        assert!(game.exploitability(&solved.to_table(&game)) < 0.01);
+
+        // A bot playing the solved chart
+        let mut strategy = PushFoldStrategy { chart: solved };
+        let played = PushFoldChart::from_strategy(&mut strategy, 10.0, 4, 1);
+        assert!(game.exploitability(&played.to_table(&game)) < 0.05);
+    }
+}
@@ -0,0 +1,526 @@
+use std::{fmt, str::FromStr};
+
+use super::components::{parse_cards, Card};
+
+/// Two-card starting hands: 52 * 51 / 2
+pub const N_COMBOS: usize = 1326;
+
+/// Kinds of starting hands: 13 pairs, 78 suited and 78 offsuit hands
+pub const N_HAND_CLASSES: usize = 169;
+
+const RANK_CHARS: &str = "23456789TJQKA";
+
+/// Two specific hole cards
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Combo {
+    /// Card indices, `high` above `low`
+    high: u8,
+    low: u8,
+}
+
+impl Combo {
+    /// [[None]] if both cards are the same
+    pub fn new(a: &Card, b: &Card) -> Option<Self> {
+        match a.0.cmp(&b.0) {
+            std::cmp::Ordering::Greater => Some(Self {
+                high: a.0,
+                low: b.0,
+            }),
+            std::cmp::Ordering::Less => Some(Self {
+                high: b.0,
+                low: a.0,
+            }),
+            std::cmp::Ordering::Equal => None,
+        }
+    }
+
+    /// Higher card first
+    pub fn cards(&self) -> [Card; 2] {
+        [Card(self.high), Card(self.low)]
+    }
+
+    /// One bit per card
+    pub fn mask(&self) -> u64 {
+        1 << self.high | 1 << self.low
+    }
+
+    fn index(&self) -> usize {
+        let (high, low) = (self.high as usize, self.low as usize);
+        high * (high - 1) / 2 + low
+    }
+
+    /// Kind of starting hand, below [[N_HAND_CLASSES]]. See [[class_notation]].
+    pub fn class_index(&self) -> usize {
+        let [high, low] = self.cards();
+        let class = if high.rank() == low.rank() {
+            HandClass::Pair(high.rank())
+        } else if high.suit() == low.suit() {
+            HandClass::Suited {
+                high: high.rank(),
+                low: low.rank(),
+            }
+        } else {
+            HandClass::Offsuit {
+                high: high.rank(),
+                low: low.rank(),
+            }
+        };
+        class.index()
+    }
+
+    /// Every combo of the deck
+    pub fn all() -> impl Iterator<Item = Combo> {
+        (1..52u8).flat_map(|high| (0..high).map(move |low| Combo { high, low }))
+    }
+}
+
+impl fmt::Display for Combo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let [high, low] = self.cards();
+        write!(f, "{}{}", high, low)
+    }
+}
+
+/// The 169 kinds of starting hands, ranks from 0 for a two to 12 for an ace
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum HandClass {
+    Pair(u8),
+    Suited { high: u8, low: u8 },
+    Offsuit { high: u8, low: u8 },
+}
+
+impl HandClass {
+    fn combos(&self) -> Vec<Combo> {
+        let card = |rank: u8, suit: u8| Card(rank * 4 + suit);
+        let mut combos = Vec::new();
+        for suit_a in 0..4 {
+            for suit_b in 0..4 {
+                let combo = match *self {
+                    HandClass::Pair(rank) if suit_a < suit_b => {
+                        Combo::new(&card(rank, suit_a), &card(rank, suit_b))
+                    }
+                    HandClass::Suited { high, low } if suit_a == suit_b => {
+                        Combo::new(&card(high, suit_a), &card(low, suit_b))
+                    }
+                    HandClass::Offsuit { high, low } if suit_a != suit_b => {
+                        Combo::new(&card(high, suit_a), &card(low, suit_b))
+                    }
+                    _ => None,
+                };
+                combos.extend(combo);
+            }
+        }
+        combos
+    }
+
+    /// On a grid of ranks: pairs on the diagonal, suited hands by high then low rank,
+    /// offsuit hands by low then high rank
+    fn index(&self) -> usize {
+        match *self {
+            HandClass::Pair(rank) => rank as usize * 14,
+            HandClass::Suited { high, low } => high as usize * 13 + low as usize,
+            HandClass::Offsuit { high, low } => low as usize * 13 + high as usize,
+        }
+    }
+
+    fn from_index(index: usize) -> Self {
+        let (row, column) = ((index / 13) as u8, (index % 13) as u8);
+        match row.cmp(&column) {
+            std::cmp::Ordering::Equal => HandClass::Pair(row),
+            std::cmp::Ordering::Greater => HandClass::Suited {
+                high: row,
+                low: column,
+            },
+            std::cmp::Ordering::Less => HandClass::Offsuit {
+                high: column,
+                low: row,
+            },
+        }
+    }
+
+    fn notation(&self) -> String {
+        let rank_char = |rank: u8| RANK_CHARS.as_bytes()[rank as usize] as char;
+        match *self {
+            HandClass::Pair(rank) => format!("{}{}", rank_char(rank), rank_char(rank)),
+            HandClass::Suited { high, low } => format!("{}{}s", rank_char(high), rank_char(low)),
+            HandClass::Offsuit { high, low } => format!("{}{}o", rank_char(high), rank_char(low)),
+        }
+    }
+}
+
+/// "AA", "AKs", "72o" for a [[Combo::class_index]]
+pub fn class_notation(class_index: usize) -> String {
+    HandClass::from_index(class_index).notation()
+}
+
+/// Combos of the kind of starting hand of [[Combo::class_index]]
+pub fn class_combos(class_index: usize) -> Vec<Combo> {
+    HandClass::from_index(class_index).combos()
+}
+
+/// Weighted set of two-card hands, written in the usual notation:
+/// `"TT+, AKs, KQo, A5s-A2s, AhKh, QJs:0.5"`.
+///
+/// A weight from 0 to 1 is the share of the combos the range holds. It is 1 unless given
+/// after a colon; later parts override earlier ones.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Range {
+    /// By [[Combo::index]]
+    weights: Vec<f32>,
+}
+
+impl Default for Range {
+    fn default() -> Self {
+        Self {
+            weights: vec![0.0; N_COMBOS],
+        }
+    }
+}
+
+impl Range {
+    pub fn from_combo(combo: Combo) -> Self {
+        let mut range = Self::default();
+        range.set_weight(combo, 1.0);
+        range
+    }
+
+    pub fn weight(&self, combo: Combo) -> f32 {
+        self.weights[combo.index()]
+    }
+
+    pub fn set_weight(&mut self, combo: Combo, weight: f32) {
+        self.weights[combo.index()] = weight;
+    }
+
+    /// Combos with a positive weight
+    pub fn combos(&self) -> impl Iterator<Item = (Combo, f32)> + '_ {
+        Combo::all()
+            .map(|combo| (combo, self.weight(combo)))
+            .filter(|(_, weight)| *weight > 0.0)
+    }
+
+    /// Weighted number of combos
+    pub fn n_combos(&self) -> f32 {
+        self.weights.iter().sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.weights.iter().all(|weight| *weight <= 0.0)
+    }
+
+    /// Without the combos holding any card of `mask`
+    pub fn without_cards(&self, mask: u64) -> Self {
+        let mut range = self.clone();
+        for combo in Combo::all().filter(|combo| combo.mask() & mask != 0) {
+            range.set_weight(combo, 0.0);
+        }
+        range
+    }
+
+    fn has_class(&self, class: HandClass, weight: f32) -> bool {
+        class
+            .combos()
+            .iter()
+            .all(|combo| self.weight(*combo) == weight)
+    }
+}
+
+impl FromStr for Range {
+    type Err = ParseRangeError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let mut range = Range::default();
+        for part in text
+            .split(',')
+            .map(str::trim)
+            .filter(|part| !part.is_empty())
+        {
+            let error = |message: &str| ParseRangeError {
+                part: part.to_string(),
+                message: message.to_string(),
+            };
+            let (hands, weight) = match part.split_once(':') {
+                Some((hands, weight)) => {
+                    let weight: f32 = weight
+                        .trim()
+                        .parse()
+                        .map_err(|_| error("weight must be a number"))?;
+                    if !(0.0..=1.0).contains(&weight) {
+                        return Err(error("weight must be from 0 to 1"));
+                    }
+                    (hands.trim(), weight)
+                }
+                None => (part, 1.0),
+            };
+            for combo in parse_hands(hands).map_err(error)? {
+                range.set_weight(combo, weight);
+            }
+        }
+        Ok(range)
+    }
+}
+
+/// Combos of one part of a range, weight aside
+fn parse_hands(hands: &str) -> Result<Vec<Combo>, &'static str> {
+    if hands.len() == 4 && !hands.contains(['+', '-']) {
+        let cards = parse_cards(hands).map_err(|_| "invalid cards")?;
+        return Combo::new(&cards[0], &cards[1])
+            .map(|combo| vec![combo])
+            .ok_or("both cards are the same");
+    }
+    let classes = if let Some((top, bottom)) = hands.split_once('-') {
+        let top = parse_classes(top)?;
+        let bottom = parse_classes(bottom)?;
+        let mut classes = Vec::new();
+        for (top, bottom) in top.into_iter().zip(bottom) {
+            classes.extend(class_span(top, bottom)?);
+        }
+        classes
+    } else if let Some(lowest) = hands.strip_suffix('+') {
+        parse_classes(lowest)?
+            .into_iter()
+            .flat_map(|lowest| match lowest {
+                HandClass::Pair(rank) => (rank..13).map(HandClass::Pair).collect(),
+                HandClass::Suited { high, low } => (low..high)
+                    .map(|low| HandClass::Suited { high, low })
+                    .collect(),
+                HandClass::Offsuit { high, low } => (low..high)
+                    .map(|low| HandClass::Offsuit { high, low })
+                    .collect::<Vec<_>>(),
+            })
+            .collect()
+    } else {
+        parse_classes(hands)?
+    };
+    Ok(classes.iter().flat_map(HandClass::combos).collect())
+}
+
+/// "TT", "AKs", "AKo" or "AK" for both suited and offsuit
+fn parse_classes(class: &str) -> Result<Vec<HandClass>, &'static str> {
+    let chars: Vec<char> = class.trim().chars().collect();
+    if !(2..=3).contains(&chars.len()) {
+        return Err("expected a hand like TT, AKs, AKo or AhKh");
+    }
+    let rank = |c: char| {
+        RANK_CHARS
+            .find(c.to_ascii_uppercase())
+            .map(|rank| rank as u8)
+            .ok_or("invalid rank")
+    };
+    let (a, b) = (rank(chars[0])?, rank(chars[1])?);
+    let (high, low) = (a.max(b), a.min(b));
+    match (high == low, chars.get(2).map(char::to_ascii_lowercase)) {
+        (true, None) => Ok(vec![HandClass::Pair(high)]),
+        (true, Some(_)) => Err("a pair is neither suited nor offsuit"),
+        (false, Some('s')) => Ok(vec![HandClass::Suited { high, low }]),
+        (false, Some('o')) => Ok(vec![HandClass::Offsuit { high, low }]),
+        (false, None) => Ok(vec![
+            HandClass::Suited { high, low },
+            HandClass::Offsuit { high, low },
+        ]),
+        (false, Some(_)) => Err("expected s for suited or o for offsuit"),
+    }
+}
+
+/// "55-22" or "A5s-A2s", both ends included
+fn class_span(top: HandClass, bottom: HandClass) -> Result<Vec<HandClass>, &'static str> {
+    match (top, bottom) {
+        (HandClass::Pair(a), HandClass::Pair(b)) => {
+            Ok((a.min(b)..=a.max(b)).map(HandClass::Pair).collect())
+        }
+        (
+            HandClass::Suited { high, low: a },
+            HandClass::Suited {
+                high: high_bottom,
+                low: b,
+            },
+        ) if high == high_bottom => Ok((a.min(b)..=a.max(b))
+            .map(|low| HandClass::Suited { high, low })
+            .collect()),
+        (
+            HandClass::Offsuit { high, low: a },
+            HandClass::Offsuit {
+                high: high_bottom,
+                low: b,
+            },
+        ) if high == high_bottom => Ok((a.min(b)..=a.max(b))
+            .map(|low| HandClass::Offsuit { high, low })
+            .collect()),
+        _ => Err("both ends of a span must be pairs, or share the high card and suitedness"),
+    }
+}
+
+/// Shortest notation: full hand classes grouped into spans, other combos one by one
+impl fmt::Display for Range {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut weights: Vec<f32> = Vec::new();
+        for (_, weight) in self.combos() {
+            if !weights.contains(&weight) {
+                weights.push(weight);
+            }
+        }
+        weights.sort_by(|a, b| b.total_cmp(a));
+
+        let mut parts = Vec::new();
+        for weight in weights {
+            let mut in_classes = Range::default();
+            let mut weight_parts = Vec::new();
+
+            let pairs: Vec<u8> = (0..13u8)
+                .rev()
+                .filter(|rank| self.has_class(HandClass::Pair(*rank), weight))
+                .collect();
+            weight_parts.extend(spans(&pairs, 12, HandClass::Pair));
+            for pair in pairs {
+                mark(&mut in_classes, HandClass::Pair(pair));
+            }
+            for is_suited in [true, false] {
+                for high in (1..13u8).rev() {
+                    let class = |low| {
+                        if is_suited {
+                            HandClass::Suited { high, low }
+                        } else {
+                            HandClass::Offsuit { high, low }
+                        }
+                    };
+                    let lows: Vec<u8> = (0..high)
+                        .rev()
+                        .filter(|low| self.has_class(class(*low), weight))
+                        .collect();
+                    weight_parts.extend(spans(&lows, high - 1, class));
+                    for low in lows {
+                        mark(&mut in_classes, class(low));
+                    }
+                }
+            }
+            for (combo, combo_weight) in self.combos() {
+                if combo_weight == weight && in_classes.weight(combo) == 0.0 {
+                    weight_parts.push(combo.to_string());
+                }
+            }
+
+            for part in weight_parts {
+                if weight == 1.0 {
+                    parts.push(part);
+                } else {
+                    parts.push(format!("{}:{}", part, weight));
+                }
+            }
+        }
+        write!(f, "{}", parts.join(", "))
+    }
+}
+
+fn mark(range: &mut Range, class: HandClass) {
+    for combo in class.combos() {
+        range.set_weight(combo, 1.0);
+    }
+}
+
+/// Notation of runs of consecutive `ranks`, given highest first. A run up to `top` is
+/// written with a plus.
+fn spans(ranks: &[u8], top: u8, class: impl Fn(u8) -> HandClass) -> Vec<String> {
+    let mut spans = Vec::new();
+    let mut start = 0;
+    while start < ranks.len() {
+        let mut end = start;
+        while end + 1 < ranks.len() && ranks[end + 1] + 1 == ranks[end] {
+            end += 1;
+        }
+        let (highest, lowest) = (class(ranks[start]), class(ranks[end]));
+        spans.push(if start == end {
+            highest.notation()
+        } else if ranks[start] == top {
+            format!("{}+", lowest.notation())
+        } else {
+            format!("{}-{}", highest.notation(), lowest.notation())
+        });
+        start = end + 1;
+    }
+    spans
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseRangeError {
+    pub part: String,
+    pub message: String,
+}
+
+impl fmt::Display for ParseRangeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid range part \"{}\": {}", self.part, self.message)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn range(text: &str) -> Range {
+        text.parse().unwrap()
+    }
+
+    fn combo(text: &str) -> Combo {
+        let cards = parse_cards(text).unwrap();
+        Combo::new(&cards[0], &cards[1]).unwrap()
+    }
+
+    #[test]
+    fn parse_classes_and_spans() {
+        let pairs = range("TT+");
+        assert_eq!(pairs.n_combos(), 30.0);
+        assert_eq!(pairs.weight(combo("AsAd")), 1.0);
+        assert_eq!(pairs.weight(combo("TsTd")), 1.0);
+        assert_eq!(pairs.weight(combo("9s9d")), 0.0);
+
+        let wheel_aces = range("A5s-A2s");
+        assert_eq!(wheel_aces.n_combos(), 16.0);
+        assert_eq!(wheel_aces.weight(combo("Ah3h")), 1.0);
+        assert_eq!(wheel_aces.weight(combo("Ah6h")), 0.0);
+        assert_eq!(wheel_aces.weight(combo("Ah3d")), 0.0);
+
+        assert_eq!(range("AK").n_combos(), 16.0);
+        assert_eq!(range("AKs").n_combos(), 4.0);
+        assert_eq!(range("AKo").n_combos(), 12.0);
+        assert_eq!(range("KA").n_combos(), 16.0);
+    }
+
+    #[test]
+    fn parse_combos_and_weights() {
+        let suited = range("AhKh");
+        assert_eq!(suited.n_combos(), 1.0);
+        assert_eq!(suited.weight(combo("KhAh")), 1.0);
+
+        let weighted = range("QQ:0.5, AKs");
+        assert_eq!(weighted.weight(combo("QcQd")), 0.5);
+        assert_eq!(weighted.weight(combo("AsKs")), 1.0);
+        assert_eq!(weighted.n_combos(), 7.0);
+    }
+
+    #[test]
+    fn parse_errors() {
+        for text in [
+            "AKx", "AAs", "ZZ", "AhAh", "A5s-K2s", "QQ:2", "QQ:half", "AKQJ", "T",
+        ] {
+            assert!(text.parse::<Range>().is_err(), "{}", text);
+        }
+        let error = "TT+, AKz".parse::<Range>().unwrap_err();
+        assert_eq!(error.part, "AKz");
+    }
+
+    #[test]
+    fn display_round_trip() {
+        for text in [
+            "TT+",
+            "A5s-A2s",
+            "AhKh",
+            "QQ:0.5",
+            "22+, A2s+, K9s+, ATo+, 76s:0.25",
+        ] {
+            let parsed = range(text);
+            assert_eq!(parsed.to_string(), text);
+            let reparsed = range(&parsed.to_string());
+            assert!(Combo::all().all(|combo| parsed.weight(combo) == reparsed.weight(combo)));
+        }
+    }
+}
@@ -1,16 +1,211 @@
+use std::collections::HashMap;
+
 use bevy::prelude::*;
+use rand_chacha::ChaCha8Rng;
+
+use super::{
+    actions::Action,
+    bot::RandomStrategy,
+    components::Chips,
+    engine::GameState,
+    scenario::Scenario,
+    strategy::{GameView, Strategy},
+    tournament::{BlindLevel, TournamentConfig},
+};
 
-use super::deck::Deck;
+/// The table as seen by the engine. Components are kept in sync with it.
+#[derive(Resource)]
+pub struct GameStateResource {
+    pub state: GameState,
+}
 
+/// Insert before [[super::TablePlugin]] to start from a scenario instead of a random handout
 #[derive(Resource)]
-pub struct DeckResource {
-    pub deck: Deck,
+pub struct ScenarioResource {
+    pub scenario: Scenario,
+}
+
+/// Insert before [[super::TablePlugin]] to choose how bot seats play
+#[derive(Resource, Default)]
+pub struct SeatStrategies {
+    /// By seat index. Seats without a strategy play [[RandomStrategy]], the hero's is
+    /// never asked.
+    pub by_seat: HashMap<usize, Box<dyn Strategy>>,
+}
+
+impl SeatStrategies {
+    /// Action of the player in action, for [[GameState::with_rng]]
+    pub fn decide(&mut self, state: &GameState, rng: &mut ChaCha8Rng) -> Action {
+        let seat_index = state.in_action.expect("A player is in action");
+        let view = GameView::new(state, seat_index);
+        match self.by_seat.get_mut(&seat_index) {
+            Some(strategy) => strategy.decide(&view, rng),
+            None => RandomStrategy.decide(&view, rng),
+        }
+    }
+}
+
+/// Which poker game is dealt
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GameVariant {
+    #[default]
+    Holdem,
+    /// Four hole cards, a hand uses exactly two of them and three board cards
+    Omaha,
+    /// Hold'em with no 2 to 5 in the deck. A flush beats a full house and A-6-7-8-9 is a
+    /// straight.
+    ShortDeck,
+}
+
+impl GameVariant {
+    pub fn n_hole_cards(&self) -> usize {
+        match self {
+            GameVariant::Holdem | GameVariant::ShortDeck => 2,
+            GameVariant::Omaha => 4,
+        }
+    }
+
+    /// Lowest card rank in the deck, 0 being a two
+    pub fn lowest_card_rank(&self) -> u8 {
+        match self {
+            GameVariant::Holdem | GameVariant::Omaha => 0,
+            GameVariant::ShortDeck => 4,
+        }
+    }
 }
 
-impl Default for DeckResource {
+/// How much a player may bet or raise
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BettingStructure {
+    /// Up to the whole stack
+    #[default]
+    NoLimit,
+    /// Up to the size of the pot after calling
+    PotLimit,
+    /// One bet size per street: the small bet preflop and on the flop, the big bet on the turn
+    /// and the river. At most [[super::betting::MAX_BETS_PER_STREET]] bets per street.
+    Limit,
+}
+
+pub const MIN_SEATS: u8 = 2;
+pub const MAX_SEATS: u8 = 10;
+
+/// Insert before [[super::TablePlugin]] to override the defaults
+#[derive(Resource, Debug, Clone)]
+pub struct TableConfig {
+    pub variant: GameVariant,
+    pub betting_structure: BettingStructure,
+    /// From [[MIN_SEATS]] to [[MAX_SEATS]]
+    pub n_seats: u8,
+    pub small_blind: Chips,
+    pub big_blind: Chips,
+    /// Dead chips posted by every player
+    pub ante: Chips,
+    /// Dead chips posted by the big blind for the whole table
+    pub big_blind_ante: Chips,
+    /// Live blind posted under the gun, usually two big blinds. The straddler acts last
+    /// preflop. Ignored on tables of fewer than 4 seats.
+    pub straddle: Option<Chips>,
+    /// Sit-and-go instead of a cash game
+    pub tournament: Option<TournamentConfig>,
+    /// Seed of the first handout, random if [[None]]
+    pub seed: Option<u64>,
+}
+
+impl Default for TableConfig {
     fn default() -> Self {
         Self {
-            deck: Deck::new_shuffled(),
+            variant: GameVariant::Holdem,
+            betting_structure: BettingStructure::NoLimit,
+            n_seats: 6,
+            small_blind: Chips(1),
+            big_blind: Chips(2),
+            ante: Chips(0),
+            big_blind_ante: Chips(0),
+            straddle: None,
+            tournament: None,
+            seed: None,
+        }
+    }
+}
+
+impl TableConfig {
+    pub fn pot_limit_omaha() -> Self {
+        Self {
+            variant: GameVariant::Omaha,
+            betting_structure: BettingStructure::PotLimit,
+            ..Default::default()
+        }
+    }
+
+    pub fn short_deck() -> Self {
+        Self {
+            variant: GameVariant::ShortDeck,
+            ..Default::default()
+        }
+    }
+
+    /// Seat shown at the bottom of the table
+    pub fn hero_seat(&self) -> u8 {
+        self.n_seats / 2
+    }
+
+    /// Stack a player sits down with and, in a cash game, is topped up to
+    pub fn buy_in(&self) -> Chips {
+        match &self.tournament {
+            Some(tournament) => tournament.starting_stack,
+            None => Chips(100 * self.big_blind.0),
+        }
+    }
+
+    /// Stakes follow the current tournament blind level
+    pub fn apply_blind_level(&mut self, level: &BlindLevel) {
+        self.small_blind = level.small_blind;
+        self.big_blind = level.big_blind;
+        self.ante = level.ante;
+    }
+
+    /// `n_players` is the number of players dealt in
+    pub fn straddle(&self, n_players: u8) -> Option<Chips> {
+        self.straddle.filter(|_| n_players >= 4)
+    }
+
+    /// Preflop position of the first player to act preflop
+    pub fn first_to_act_preflop(&self, n_players: u8) -> u8 {
+        if self.straddle(n_players).is_some() {
+            n_players - 2
+        } else {
+            n_players - 1
         }
     }
+
+    /// Live blind and dead ante owed by the player in `preflop_position`
+    pub fn forced_bets(&self, preflop_position: u8, n_players: u8) -> (Chips, Chips) {
+        let blind = match preflop_position {
+            0 => self.big_blind,
+            1 => self.small_blind,
+            p if p == n_players - 1 => self.straddle(n_players).unwrap_or(Chips(0)),
+            _ => Chips(0),
+        };
+        let ante = if preflop_position == 0 {
+            self.ante.0 + self.big_blind_ante.0
+        } else {
+            self.ante.0
+        };
+        (blind, Chips(ante))
+    }
+
+    /// Size of a bet or a raise in fixed-limit: the big blind, doubled from the turn on
+    pub fn limit_bet_size(&self, street: u8) -> Chips {
+        if street < 2 {
+            self.big_blind
+        } else {
+            Chips(2 * self.big_blind.0)
+        }
+    }
+
+    /// Largest live blind: the amount to call preflop
+    pub fn largest_blind(&self, n_players: u8) -> Chips {
+        self.straddle(n_players).unwrap_or(self.big_blind)
+    }
 }
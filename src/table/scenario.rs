@@ -0,0 +1,394 @@
+use std::{fmt, path::Path};
+
+use super::{
+    components::{Card, Chips},
+    resources::{TableConfig, MAX_SEATS, MIN_SEATS},
+};
+
+/// Preset start of the first handout, for testing and practice. Cards left out are dealt at
+/// random from the rest of the deck.
+///
+/// Loaded from a text file, one setting per line, `#` starting a comment:
+/// ```text
+/// # Hero's aces run into a set of sevens on the flop
+/// seats 6
+/// button 5
+/// stack 0 300
+/// stack 3 300
+/// hole 0 7c 7d
+/// hole 3 Ah As
+/// board 7h 2s Kd
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Scenario {
+    /// Overrides [[TableConfig::n_seats]]
+    pub n_seats: Option<u8>,
+    /// Seat index of the button, the hero's right by default
+    pub button_seat: Option<u8>,
+    /// Starting stacks by seat index, the buy-in for the others
+    pub stacks: Vec<(u8, Chips)>,
+    /// Hole cards by seat index
+    pub hole_cards: Vec<(u8, Vec<Card>)>,
+    /// Flop, turn and river cards in order, up to five
+    pub board: Vec<Card>,
+}
+
+impl Scenario {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ScenarioError> {
+        let text = std::fs::read_to_string(path.as_ref()).map_err(|error| {
+            ScenarioError::Unreadable(format!("{}: {}", path.as_ref().display(), error))
+        })?;
+        text.parse()
+    }
+
+    /// Applies the seat count to `config`
+    pub fn configure(&self, config: &mut TableConfig) {
+        if let Some(n_seats) = self.n_seats {
+            config.n_seats = n_seats;
+        }
+    }
+
+    /// Checks the scenario fits a table of `config`, once configured
+    pub fn validate(&self, config: &TableConfig) -> Result<(), ScenarioError> {
+        let n_seats = self.n_seats.unwrap_or(config.n_seats);
+        if !(MIN_SEATS..=MAX_SEATS).contains(&n_seats) {
+            return Err(ScenarioError::WrongSeatCount(n_seats));
+        }
+        let seat_indices = self
+            .button_seat
+            .iter()
+            .chain(self.stacks.iter().map(|(seat_index, _)| seat_index))
+            .chain(self.hole_cards.iter().map(|(seat_index, _)| seat_index));
+        for &seat_index in seat_indices {
+            if seat_index >= n_seats {
+                return Err(ScenarioError::SeatOutOfRange(seat_index));
+            }
+        }
+        if let Some((seat_index, _)) = self.stacks.iter().find(|(_, stack)| stack.0 == 0) {
+            return Err(ScenarioError::EmptyStack(*seat_index));
+        }
+        for (seat_index, cards) in self.hole_cards.iter() {
+            if cards.len() != config.variant.n_hole_cards() {
+                return Err(ScenarioError::WrongHoleCardCount {
+                    seat_index: *seat_index,
+                    expected: config.variant.n_hole_cards(),
+                    got: cards.len(),
+                });
+            }
+        }
+        if self.board.len() > 5 {
+            return Err(ScenarioError::TooManyBoardCards(self.board.len()));
+        }
+        let mut is_used = [false; 52];
+        for card in self.cards() {
+            if card.rank() < config.variant.lowest_card_rank() {
+                return Err(ScenarioError::CardNotInDeck(card.clone()));
+            }
+            if is_used[card.0 as usize] {
+                return Err(ScenarioError::DuplicateCard(card.clone()));
+            }
+            is_used[card.0 as usize] = true;
+        }
+        Ok(())
+    }
+
+    /// Preset cards in the order they are drawn, [[None]] for a random card. Hole cards go to
+    /// the players not eliminated, in seat order, then the board is dealt.
+    pub fn draw_order(&self, dealt_in: &[bool], n_hole_cards: usize) -> Vec<Option<Card>> {
+        let mut order = Vec::new();
+        for (seat_index, _) in dealt_in
+            .iter()
+            .enumerate()
+            .filter(|(_, &is_dealt)| is_dealt)
+        {
+            match self
+                .hole_cards
+                .iter()
+                .find(|(preset_seat_index, _)| *preset_seat_index as usize == seat_index)
+            {
+                Some((_, cards)) => order.extend(cards.iter().cloned().map(Some)),
+                None => order.extend(std::iter::repeat_n(None, n_hole_cards)),
+            }
+        }
+        for street_card_index in 0..5 {
+            order.push(self.board.get(street_card_index).cloned());
+        }
+        order
+    }
+
+    fn cards(&self) -> impl Iterator<Item = &Card> {
+        self.hole_cards
+            .iter()
+            .flat_map(|(_, cards)| cards.iter())
+            .chain(self.board.iter())
+    }
+}
+
+impl std::str::FromStr for Scenario {
+    type Err = ScenarioError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let mut scenario = Scenario::default();
+        for (line_index, line) in text.lines().enumerate() {
+            let line_error = |message: String| ScenarioError::Syntax {
+                line: line_index + 1,
+                message,
+            };
+            let line = line.split('#').next().unwrap_or_default();
+            let mut words = line.split_whitespace();
+            let Some(keyword) = words.next() else {
+                continue;
+            };
+            let words: Vec<&str> = words.collect();
+            let number = |word: Option<&&str>| -> Result<u32, ScenarioError> {
+                word.and_then(|word| word.parse().ok())
+                    .ok_or_else(|| line_error(format!("{} expects a number", keyword)))
+            };
+            let small_number = |word: Option<&&str>| -> Result<u8, ScenarioError> {
+                u8::try_from(number(word)?)
+                    .map_err(|_| line_error(format!("{} expects a number up to 255", keyword)))
+            };
+            let cards = |words: &[&str]| -> Result<Vec<Card>, ScenarioError> {
+                words
+                    .iter()
+                    .map(|word| {
+                        word.parse()
+                            .map_err(|error| line_error(format!("{}", error)))
+                    })
+                    .collect()
+            };
+            match keyword {
+                "seats" => scenario.n_seats = Some(small_number(words.first())?),
+                "button" => scenario.button_seat = Some(small_number(words.first())?),
+                "stack" => {
+                    let seat_index = small_number(words.first())?;
+                    let stack = number(words.get(1))?;
+                    scenario.stacks.push((seat_index, Chips(stack)));
+                }
+                "hole" => {
+                    let seat_index = small_number(words.first())?;
+                    if scenario
+                        .hole_cards
+                        .iter()
+                        .any(|(preset_seat_index, _)| *preset_seat_index == seat_index)
+                    {
+                        return Err(line_error(format!(
+                            "seat {} has hole cards already",
+                            seat_index
+                        )));
+                    }
+                    scenario
+                        .hole_cards
+                        .push((seat_index, cards(words.get(1..).unwrap_or_default())?));
+                }
+                "board" => scenario.board = cards(&words)?,
+                _ => return Err(line_error(format!("unknown setting \"{}\"", keyword))),
+            }
+        }
+        Ok(scenario)
+    }
+}
+
+/// Why a scenario cannot be loaded or played
+#[derive(Debug, Clone)]
+pub enum ScenarioError {
+    Unreadable(String),
+    Syntax {
+        line: usize,
+        message: String,
+    },
+    WrongSeatCount(u8),
+    SeatOutOfRange(u8),
+    EmptyStack(u8),
+    WrongHoleCardCount {
+        seat_index: u8,
+        expected: usize,
+        got: usize,
+    },
+    TooManyBoardCards(usize),
+    /// Below the lowest rank of a short deck
+    CardNotInDeck(Card),
+    DuplicateCard(Card),
+}
+
+impl fmt::Display for ScenarioError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScenarioError::Unreadable(error) => write!(f, "cannot read {}", error),
+            ScenarioError::Syntax { line, message } => write!(f, "line {}: {}", line, message),
+            ScenarioError::WrongSeatCount(n_seats) => write!(
+                f,
+                "{} seats, the table takes {} to {}",
+                n_seats, MIN_SEATS, MAX_SEATS
+            ),
+            ScenarioError::SeatOutOfRange(seat_index) => {
+                write!(f, "no seat {} at the table", seat_index)
+            }
+            ScenarioError::EmptyStack(seat_index) => {
+                write!(f, "seat {} starts with no chips", seat_index)
+            }
+            ScenarioError::WrongHoleCardCount {
+                seat_index,
+                expected,
+                got,
+            } => write!(
+                f,
+                "seat {} has {} hole cards, expected {}",
+                seat_index, got, expected
+            ),
+            ScenarioError::TooManyBoardCards(n_cards) => {
+                write!(f, "{} board cards, at most 5", n_cards)
+            }
+            ScenarioError::CardNotInDeck(card) => write!(f, "{} is not in the deck", card),
+            ScenarioError::DuplicateCard(card) => write!(f, "{} is dealt twice", card),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::table::{
+        actions::Action, components::parse_cards, engine::GameState, resources::GameVariant,
+    };
+
+    const SAMPLE: &str = include_str!("../../scenarios/aces_against_a_set.txt");
+
+    fn codes(cards: &[Card]) -> Vec<u8> {
+        cards.iter().map(|card| card.0).collect()
+    }
+
+    fn card_codes(text: &str) -> Vec<u8> {
+        codes(&parse_cards(text).unwrap())
+    }
+
+    fn invalid(text: &str, config: &TableConfig) -> ScenarioError {
+        let scenario: Scenario = text.parse().unwrap();
+        scenario.validate(config).unwrap_err()
+    }
+
+    #[test]
+    fn parse_the_sample() {
+        let scenario: Scenario = SAMPLE.parse().unwrap();
+        assert_eq!(scenario.n_seats, Some(6));
+        assert_eq!(scenario.button_seat, Some(5));
+        assert_eq!(scenario.stacks.len(), 2);
+        assert_eq!(scenario.stacks[1].0, 3);
+        assert_eq!(scenario.stacks[1].1, Chips(300));
+        assert_eq!(scenario.hole_cards[1].0, 3);
+        assert_eq!(codes(&scenario.hole_cards[1].1), card_codes("AhAs"));
+        assert_eq!(codes(&scenario.board), card_codes("7h2sKd"));
+        assert!(scenario.validate(&TableConfig::default()).is_ok());
+    }
+
+    #[test]
+    fn syntax_errors() {
+        let cases = [
+            ("seats 6\nflop 7h 2s Kd", 2),
+            ("seats six", 1),
+            ("seats 600", 1),
+            ("stack 0", 1),
+            ("# Comment\nhole 0 Ah Zz", 2),
+            ("hole 0 Ah As\nhole 0 Kh Ks", 2),
+        ];
+        for (text, expected_line) in cases {
+            match text.parse::<Scenario>() {
+                Err(ScenarioError::Syntax { line, .. }) => {
+                    assert_eq!(line, expected_line, "{}", text)
+                }
+                other => panic!("{:?} for {}", other, text),
+            }
+        }
+        assert!(matches!(
+            Scenario::load("scenarios/missing.txt"),
+            Err(ScenarioError::Unreadable(_))
+        ));
+    }
+
+    #[test]
+    fn validation_errors() {
+        let config = TableConfig::default();
+        assert!(matches!(
+            invalid("seats 11", &config),
+            ScenarioError::WrongSeatCount(11)
+        ));
+        assert!(matches!(
+            invalid("seats 3\nbutton 3", &config),
+            ScenarioError::SeatOutOfRange(3)
+        ));
+        assert!(matches!(
+            invalid("stack 2 0", &config),
+            ScenarioError::EmptyStack(2)
+        ));
+        assert!(matches!(
+            invalid("hole 1 Ah", &config),
+            ScenarioError::WrongHoleCardCount {
+                seat_index: 1,
+                expected: 2,
+                got: 1
+            }
+        ));
+        assert!(matches!(
+            invalid("board 2c 3c 4c 5c 6c 7c", &config),
+            ScenarioError::TooManyBoardCards(6)
+        ));
+        assert!(matches!(
+            invalid("hole 0 Ah As\nboard Kd As", &config),
+            ScenarioError::DuplicateCard(card) if card.to_string() == "As"
+        ));
+        let short_deck = TableConfig {
+            variant: GameVariant::ShortDeck,
+            ..Default::default()
+        };
+        assert!(matches!(
+            invalid("board 6h 5h 9c", &short_deck),
+            ScenarioError::CardNotInDeck(card) if card.to_string() == "5h"
+        ));
+    }
+
+    #[test]
+    fn draw_order_skips_seats_not_dealt_in() {
+        let scenario: Scenario = "hole 2 Ah As\nboard 7h 2s".parse().unwrap();
+        let order = scenario.draw_order(&[true, false, true], 2);
+        let order: Vec<Option<u8>> = order
+            .iter()
+            .map(|card| card.as_ref().map(|card| card.0))
+            .collect();
+        let preset = card_codes("AhAs7h2s");
+        let expected = vec![
+            None,
+            None,
+            Some(preset[0]),
+            Some(preset[1]),
+            Some(preset[2]),
+            Some(preset[3]),
+            None,
+            None,
+            None,
+        ];
+        assert_eq!(order, expected);
+    }
+
+    #[test]
+    fn sample_is_dealt_as_preset() {
+        let scenario: Scenario = SAMPLE.parse().unwrap();
+        let names: Vec<String> = (0..6).map(|i| format!("Player {}", i)).collect();
+        let mut state =
+            GameState::from_scenario(TableConfig::default(), &names, &scenario).unwrap();
+        assert_eq!(codes(&state.seats[0].hole_cards), card_codes("7c7d"));
+        assert_eq!(codes(&state.seats[3].hole_cards), card_codes("AhAs"));
+        assert_eq!(state.seats[0].stack.0 + state.seats[0].contribution.0, 300);
+        while state.street == 0 {
+            if let Some(legal_actions) = state.legal_actions() {
+                let action = match legal_actions.to_call {
+                    None => Action::Check,
+                    Some(to_call) => Action::Call(to_call),
+                };
+                state.apply_action(action).unwrap();
+            } else {
+                state.advance();
+            }
+        }
+        assert_eq!(codes(&state.board[..3]), card_codes("7h2sKd"));
+    }
+}
@@ -2,60 +2,108 @@ use bevy::prelude::*;
 
 use super::{
     components::{
-        Bankroll, BoardBundle, BoardCards, Card, Chips, HoleCards, HoleCardsFaceUp, MovedChips,
-        Player, PlayerBundle, PlayerInAction, PlayerIsActive, PlayerIsHero, PlayerName, PooledPot,
-        Pot, PreflopPosition, SeatIndex, Stack, Street,
+        Bankroll, BoardBundle, BoardCards, HoleCards, HoleCardsFaceUp, MovedChips, Player,
+        PlayerBundle, PlayerInAction, PlayerIsActive, PlayerIsHero, PlayerName, PooledPot, Pot,
+        PreflopPosition, SeatIndex, Stack, Street,
     },
-    resources::DeckResource,
+    engine::GameState,
+    profile::{Profile, ProfileStrategy},
+    resources::{GameStateResource, ScenarioResource, SeatStrategies, TableConfig},
 };
 
-const PLAYER_NAMES: [&str; 6] = ["Adam", "John", "Jane", "You", "Sarah", "Mike"];
+const OPPONENT_NAMES: [&str; 9] = [
+    "Adam", "John", "Jane", "Sarah", "Mike", "Emma", "Tom", "Lucy", "Paul",
+];
 
-const INTITIAL_PREFLOP_POSITIONS: [u8; 6] = [3, 2, 1, 0, 5, 4];
+pub fn setup_table(
+    commands: Commands,
+    mut config: ResMut<TableConfig>,
+    mut strategies: ResMut<SeatStrategies>,
+) {
+    let state = GameState::new(config.clone(), &seat_names(&config));
+    // Stakes of the first tournament level
+    *config = state.config.clone();
+    assign_profiles(&state, &mut strategies);
+    spawn_table(commands, state);
+}
+
+/// Starts from [[ScenarioResource]] instead of a random handout
+pub fn setup_scenario_table(
+    commands: Commands,
+    mut config: ResMut<TableConfig>,
+    mut strategies: ResMut<SeatStrategies>,
+    scenario: Res<ScenarioResource>,
+) {
+    let scenario = &scenario.scenario;
+    scenario.configure(&mut config);
+    let state = GameState::from_scenario(config.clone(), &seat_names(&config), scenario)
+        .unwrap_or_else(|error| panic!("Invalid scenario: {}", error));
+    *config = state.config.clone();
+    assign_profiles(&state, &mut strategies);
+    spawn_table(commands, state);
+}
+
+/// Opponents without a strategy of their own play the profiles in turn
+fn assign_profiles(state: &GameState, strategies: &mut SeatStrategies) {
+    let hero_seat = state.config.hero_seat() as usize;
+    let mut profiles = Profile::all().into_iter().cycle();
+    for (seat_index, seat) in state.seats.iter().enumerate() {
+        if seat_index == hero_seat || strategies.by_seat.contains_key(&seat_index) {
+            continue;
+        }
+        let profile = profiles.next().unwrap();
+        info!("{} plays as a {}", seat.name, profile.name);
+        strategies
+            .by_seat
+            .insert(seat_index, Box::new(ProfileStrategy { profile }));
+    }
+}
+
+fn seat_names(config: &TableConfig) -> Vec<String> {
+    let hero_seat = config.hero_seat();
+    let mut opponent_names = OPPONENT_NAMES.iter();
+    (0..config.n_seats)
+        .map(|seat_index| {
+            if seat_index == hero_seat {
+                "You".to_string()
+            } else {
+                opponent_names.next().unwrap().to_string()
+            }
+        })
+        .collect()
+}
 
-pub fn setup_table(mut commands: Commands, mut deck_resource: ResMut<DeckResource>) {
-    let deck = &mut deck_resource.deck;
-    for seat_index in 0..6 {
-        let blind = if seat_index == 3 {
-            Chips(2)
-        } else if seat_index == 2 {
-            Chips(1)
-        } else {
-            Chips(0)
-        };
+fn spawn_table(mut commands: Commands, state: GameState) {
+    let hero_seat = state.config.hero_seat();
+    for (seat_index, seat) in state.seats.iter().enumerate() {
         let player_id: Entity = commands
             .spawn(PlayerBundle {
                 player: Player {},
                 seat_index: SeatIndex(seat_index as u8),
-                name: PlayerName(PLAYER_NAMES[seat_index].to_string()),
-                bankroll: Bankroll(Chips(2000)),
-                stack: Stack(Chips(200)),
-                moved_chips: MovedChips(blind),
-                hole_cards: HoleCards([deck.draw(), deck.draw()]),
-                preflop_position: PreflopPosition(INTITIAL_PREFLOP_POSITIONS[seat_index]),
+                name: PlayerName(seat.name.clone()),
+                bankroll: Bankroll(seat.bankroll),
+                stack: Stack(seat.stack),
+                moved_chips: MovedChips(seat.front),
+                hole_cards: HoleCards(seat.hole_cards.clone()),
+                preflop_position: PreflopPosition(seat.preflop_position),
                 is_active: PlayerIsActive {},
             })
             .id();
-        if seat_index == 3 {
+        if seat_index == hero_seat as usize {
             commands
                 .entity(player_id)
                 .insert((PlayerIsHero, HoleCardsFaceUp));
         }
-        if seat_index == 4 {
+        if state.in_action == Some(seat_index) {
             commands.entity(player_id).insert(PlayerInAction);
-            println!("Player in action (setup): {}", PLAYER_NAMES[seat_index]);
+            println!("Player in action (setup): {}", seat.name);
         }
     }
     commands.spawn(BoardBundle {
-        board_cards: BoardCards([
-            Card::default(),
-            Card::default(),
-            Card::default(),
-            Card::default(),
-            Card::default(),
-        ]),
-        pot: Pot(Chips(3)),
-        pooled_pot: PooledPot(Chips(0)),
-        street: Street(0),
+        board_cards: BoardCards(state.board.clone()),
+        pot: Pot(state.pot),
+        pooled_pot: PooledPot(state.pooled_pot),
+        street: Street(state.street),
     });
+    commands.insert_resource(GameStateResource { state });
 }
@@ -0,0 +1,223 @@
+use rand_chacha::ChaCha8Rng;
+
+use super::{
+    actions::Action,
+    components::Chips,
+    engine::{GameEvent, GameState, Phase},
+    resources::TableConfig,
+};
+
+/// Deep enough for no seat to run out of top-ups, whatever the results
+const SIMULATION_BANKROLL: Chips = Chips(1_000_000_000);
+
+/// Results of one seat over a simulation
+#[derive(Debug, Clone, Default)]
+pub struct SeatStats {
+    pub name: String,
+    pub n_hands: u32,
+    /// In chips
+    pub net: i64,
+    /// Net won in the handouts that reached a showdown
+    pub showdown_net: i64,
+    /// Handouts the seat saw to the showdown
+    pub n_showdowns: u32,
+    /// Showdowns that gave the seat more chips than it put in
+    pub n_showdowns_won: u32,
+    /// Per handout, for the variance
+    sum_of_squares_bb: f64,
+}
+
+impl SeatStats {
+    pub fn bb_per_100(&self, big_blind: Chips) -> f64 {
+        if self.n_hands == 0 {
+            return 0.0;
+        }
+        self.net as f64 / big_blind.0 as f64 / self.n_hands as f64 * 100.0
+    }
+
+    /// Half width of the 95% confidence interval of [[SeatStats::bb_per_100]]
+    pub fn bb_per_100_margin(&self, big_blind: Chips) -> f64 {
+        if self.n_hands < 2 {
+            return f64::INFINITY;
+        }
+        let n = self.n_hands as f64;
+        let mean = self.net as f64 / big_blind.0 as f64 / n;
+        let variance = (self.sum_of_squares_bb - n * mean * mean) / (n - 1.0);
+        1.96 * (variance.max(0.0) / n).sqrt() * 100.0
+    }
+
+    /// Share of the handouts that went to showdown
+    pub fn went_to_showdown(&self) -> f64 {
+        ratio(self.n_showdowns, self.n_hands)
+    }
+
+    /// Share of the showdowns won
+    pub fn won_at_showdown(&self) -> f64 {
+        ratio(self.n_showdowns_won, self.n_showdowns)
+    }
+}
+
+fn ratio(count: u32, total: u32) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        count as f64 / total as f64
+    }
+}
+
+/// Cash game played as fast as the engine goes: no window, no thinking delays.
+/// Bankrolls are deep enough for every seat to keep topping up.
+pub struct Simulation {
+    pub state: GameState,
+    pub stats: Vec<SeatStats>,
+    /// Chips owned by each seat when the current handout started, before the forced bets
+    owned_at_start: Vec<u32>,
+    shows_cards: Vec<bool>,
+}
+
+impl Simulation {
+    pub fn new(config: TableConfig, names: &[String]) -> Self {
+        assert!(config.tournament.is_none(), "Simulations are of cash games");
+        let mut state = GameState::new(config, names);
+        for seat in state.seats.iter_mut() {
+            seat.bankroll = SIMULATION_BANKROLL;
+        }
+        let stats = names
+            .iter()
+            .map(|name| SeatStats {
+                name: name.clone(),
+                ..Default::default()
+            })
+            .collect();
+        let mut simulation = Self {
+            owned_at_start: vec![0; names.len()],
+            shows_cards: vec![false; names.len()],
+            state,
+            stats,
+        };
+        simulation.start_handout();
+        simulation
+    }
+
+    /// Plays `n_hands` more handouts, every decision taken by `decide`
+    pub fn run(
+        &mut self,
+        n_hands: u32,
+        mut decide: impl FnMut(&GameState, &mut ChaCha8Rng) -> Action,
+    ) {
+        let mut n_hands_played = 0;
+        while n_hands_played < n_hands {
+            if self.state.phase == Phase::Betting {
+                let action = self.state.with_rng(&mut decide);
+                let seat_index = self.state.in_action.unwrap_or_default();
+                if let Err(error) = self.state.apply_action(action) {
+                    panic!(
+                        "Illegal action {} by {} with seed {}: {}",
+                        action, self.state.seats[seat_index].name, self.state.hand_seed, error
+                    );
+                }
+                continue;
+            }
+            let was_handout_ended = self.state.phase == Phase::HandoutEnded;
+            for event in self.state.advance() {
+                if let GameEvent::CardsShown { seat_index } = event {
+                    self.shows_cards[seat_index] = true;
+                }
+            }
+            if was_handout_ended {
+                self.start_handout();
+            } else if self.state.phase == Phase::HandoutEnded {
+                self.end_handout();
+                n_hands_played += 1;
+            }
+        }
+    }
+
+    fn start_handout(&mut self) {
+        for (seat_index, seat) in self.state.seats.iter().enumerate() {
+            // Blinds, straddle and antes are all in the contribution
+            self.owned_at_start[seat_index] = seat.stack.0 + seat.contribution.0 + seat.bankroll.0;
+            self.shows_cards[seat_index] = false;
+        }
+    }
+
+    fn end_handout(&mut self) {
+        let big_blind = self.state.config.big_blind.0 as f64;
+        for (seat_index, seat) in self.state.seats.iter().enumerate() {
+            // The front holds the winnings once the pots are settled
+            let owned = seat.stack.0 + seat.front.0 + seat.bankroll.0;
+            let net = owned as i64 - self.owned_at_start[seat_index] as i64;
+            let stats = &mut self.stats[seat_index];
+            stats.n_hands += 1;
+            stats.net += net;
+            stats.sum_of_squares_bb += (net as f64 / big_blind).powi(2);
+            if self.shows_cards[seat_index] {
+                stats.n_showdowns += 1;
+                stats.showdown_net += net;
+                if net > 0 {
+                    stats.n_showdowns_won += 1;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::table::resources::SeatStrategies;
+
+    fn antes_and_straddle() -> TableConfig {
+        TableConfig {
+            ante: Chips(1),
+            straddle: Some(Chips(4)),
+            seed: Some(7),
+            ..Default::default()
+        }
+    }
+
+    fn names(config: &TableConfig) -> Vec<String> {
+        (0..config.n_seats).map(|i| format!("Bot {}", i)).collect()
+    }
+
+    #[test]
+    fn folds_to_the_straddle() {
+        let config = antes_and_straddle();
+        let n_seats = config.n_seats;
+        let mut simulation = Simulation::new(config.clone(), &names(&config));
+        let positions: Vec<u8> = simulation
+            .state
+            .seats
+            .iter()
+            .map(|seat| seat.preflop_position)
+            .collect();
+        simulation.run(1, |state, _| {
+            if state.legal_actions().unwrap().can_check() {
+                Action::Check
+            } else {
+                Action::Fold
+            }
+        });
+        for (seat_index, stats) in simulation.stats.iter().enumerate() {
+            // The straddler takes the blinds and everyone's ante
+            let expected = match positions[seat_index] {
+                0 => -3,
+                1 => -2,
+                position if position == n_seats - 1 => 8,
+                _ => -1,
+            };
+            assert_eq!(stats.net, expected, "Seat {}", seat_index);
+        }
+    }
+
+    #[test]
+    fn nets_sum_to_zero_with_antes_and_straddle() {
+        let config = antes_and_straddle();
+        let mut simulation = Simulation::new(config.clone(), &names(&config));
+        let mut strategies = SeatStrategies::default();
+        simulation.run(2000, |state, rng| strategies.decide(state, rng));
+        assert!(simulation.stats.iter().all(|stats| stats.net != 0));
+        let total_net: i64 = simulation.stats.iter().map(|stats| stats.net).sum();
+        assert_eq!(total_net, 0);
+    }
+}
@@ -1,5 +1,8 @@
 use bevy::prelude::*;
 
+use super::engine::Phase;
+
+/// Mirrors the engine [[Phase]]
 #[derive(States, Default, Debug, Clone, PartialEq, Eq, Hash)]
 pub enum HandoutState {
     #[default]
@@ -7,9 +10,24 @@ pub enum HandoutState {
     ExpectingPool,
     ExpectingDeal,
     ExpectingShowdown,
-    ShowdownMade,
     ExpectingWinningsAttribution,
     HandoutEnded,
+    /// Sit-and-go is over: one player has all the chips
+    TournamentFinished,
+}
+
+impl From<Phase> for HandoutState {
+    fn from(phase: Phase) -> Self {
+        match phase {
+            Phase::Betting => HandoutState::ExpectingMove,
+            Phase::BettingRoundOver => HandoutState::ExpectingPool,
+            Phase::Dealing => HandoutState::ExpectingDeal,
+            Phase::Showdown => HandoutState::ExpectingShowdown,
+            Phase::Settling => HandoutState::ExpectingWinningsAttribution,
+            Phase::HandoutEnded => HandoutState::HandoutEnded,
+            Phase::TournamentFinished => HandoutState::TournamentFinished,
+        }
+    }
 }
 
 #[derive(States, Default, Debug, Clone, PartialEq, Eq, Hash)]
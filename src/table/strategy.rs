@@ -0,0 +1,116 @@
+use rand_chacha::ChaCha8Rng;
+
+use super::{
+    actions::{Action, LegalActions},
+    betting::Betting,
+    components::{Card, Chips},
+    engine::{ActionRecord, GameState},
+    resources::TableConfig,
+};
+
+/// Decision maker of a bot seat, assigned through [[super::resources::SeatStrategies]]
+pub trait Strategy: Send + Sync {
+    /// Action of the seat in action of `view`. `rng` is the random generator of the handout,
+    /// so that decisions replay along with the cards.
+    fn decide(&mut self, view: &GameView, rng: &mut ChaCha8Rng) -> Action;
+}
+
+/// The table as seen from one seat: the hole cards of the others stay hidden
+pub struct GameView<'a> {
+    state: &'a GameState,
+    seat_index: usize,
+}
+
+/// What anyone at the table knows about a seat
+#[derive(Debug, Clone)]
+pub struct SeatView<'a> {
+    pub name: &'a str,
+    pub stack: Chips,
+    /// Chips in front of the player on this street
+    pub front: Chips,
+    /// See [[super::components::PreflopPosition]]
+    pub preflop_position: u8,
+    /// Dealt in and not folded
+    pub is_active: bool,
+    pub is_all_in: bool,
+    pub is_eliminated: bool,
+}
+
+impl<'a> GameView<'a> {
+    pub fn new(state: &'a GameState, seat_index: usize) -> Self {
+        Self { state, seat_index }
+    }
+
+    pub fn seat_index(&self) -> usize {
+        self.seat_index
+    }
+
+    pub fn config(&self) -> &TableConfig {
+        &self.state.config
+    }
+
+    pub fn hole_cards(&self) -> &[Card] {
+        &self.state.seats[self.seat_index].hole_cards
+    }
+
+    /// Community cards dealt so far
+    pub fn board(&self) -> &[Card] {
+        let n_dealt = self
+            .state
+            .board
+            .iter()
+            .take_while(|card| card.is_defined())
+            .count();
+        &self.state.board[..n_dealt]
+    }
+
+    /// 0 preflop to 3 on the river
+    pub fn street(&self) -> u8 {
+        self.state.street
+    }
+
+    /// All chips in play, including those in front of the players
+    pub fn pot(&self) -> Chips {
+        self.state.pot
+    }
+
+    /// Indexed by seat index
+    pub fn seats(&self) -> Vec<SeatView<'a>> {
+        self.state
+            .seats
+            .iter()
+            .map(|seat| SeatView {
+                name: &seat.name,
+                stack: seat.stack,
+                front: seat.front,
+                preflop_position: seat.preflop_position,
+                is_active: seat.is_active,
+                is_all_in: seat.is_all_in,
+                is_eliminated: seat.is_eliminated,
+            })
+            .collect()
+    }
+
+    /// This seat
+    pub fn seat(&self) -> SeatView<'a> {
+        self.seats().swap_remove(self.seat_index)
+    }
+
+    /// Actions of the current handout, in order
+    pub fn history(&self) -> &[ActionRecord] {
+        &self.state.history
+    }
+
+    pub fn betting(&self) -> Betting {
+        self.state.betting()
+    }
+
+    /// Chips to add to call, 0 when checking is possible
+    pub fn to_call(&self) -> Chips {
+        Chips(self.betting().tocall())
+    }
+
+    pub fn legal_actions(&self) -> LegalActions {
+        self.betting().legal_actions()
+    }
+}
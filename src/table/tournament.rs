@@ -0,0 +1,165 @@
+use std::time::Duration;
+
+use super::components::Chips;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BlindLevel {
+    pub small_blind: Chips,
+    pub big_blind: Chips,
+    pub ante: Chips,
+}
+
+impl BlindLevel {
+    pub fn new(small_blind: u32, big_blind: u32, ante: u32) -> Self {
+        Self {
+            small_blind: Chips(small_blind),
+            big_blind: Chips(big_blind),
+            ante: Chips(ante),
+        }
+    }
+}
+
+/// When blinds go up
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LevelDuration {
+    Hands(u32),
+    Time(Duration),
+}
+
+/// Sit-and-go: fixed starting stacks, rising blinds, no top-ups
+#[derive(Debug, Clone)]
+pub struct TournamentConfig {
+    /// Paid by every player into the prize pool
+    pub buy_in: u32,
+    pub starting_stack: Chips,
+    /// The last level stays until the end
+    pub levels: Vec<BlindLevel>,
+    pub level_duration: LevelDuration,
+    /// Percentages of the prize pool, first place first
+    pub payouts: Vec<u32>,
+}
+
+impl TournamentConfig {
+    pub fn sit_and_go(n_seats: u8) -> Self {
+        Self {
+            buy_in: 10,
+            starting_stack: Chips(1500),
+            levels: vec![
+                BlindLevel::new(10, 20, 0),
+                BlindLevel::new(15, 30, 0),
+                BlindLevel::new(25, 50, 0),
+                BlindLevel::new(50, 100, 0),
+                BlindLevel::new(75, 150, 0),
+                BlindLevel::new(100, 200, 25),
+                BlindLevel::new(150, 300, 25),
+                BlindLevel::new(200, 400, 50),
+                BlindLevel::new(300, 600, 75),
+                BlindLevel::new(400, 800, 100),
+            ],
+            level_duration: LevelDuration::Hands(10),
+            payouts: match n_seats {
+                2..=3 => vec![100],
+                4..=6 => vec![65, 35],
+                _ => vec![50, 30, 20],
+            },
+        }
+    }
+
+    pub fn prize_pool(&self, n_entrants: u8) -> u32 {
+        self.buy_in * n_entrants as u32
+    }
+
+    /// Payout for finishing in `place`, 1 being the winner. The winner gets the rounding leftover.
+    pub fn payout(&self, place: u8, n_entrants: u8) -> u32 {
+        let prize_pool = self.prize_pool(n_entrants);
+        let paid: Vec<u32> = self
+            .payouts
+            .iter()
+            .map(|percentage| prize_pool * percentage / 100)
+            .collect();
+        match place as usize {
+            0 => 0,
+            1 => paid[0] + prize_pool - paid.iter().sum::<u32>(),
+            p if p <= paid.len() => paid[p - 1],
+            _ => 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct TournamentResult {
+    pub seat_index: u8,
+    pub name: String,
+    pub place: u8,
+    pub payout: u32,
+}
+
+/// Progress of the tournament, present only in tournament mode
+#[derive(Debug, Clone)]
+pub struct TournamentState {
+    pub level: usize,
+    pub hands_in_level: u32,
+    pub level_started_at: Duration,
+    pub n_entrants: u8,
+    /// Worst place first
+    pub eliminated: Vec<(u8, String)>,
+    pub results: Vec<TournamentResult>,
+}
+
+impl TournamentState {
+    pub fn new(n_entrants: u8, now: Duration) -> Self {
+        Self {
+            level: 0,
+            hands_in_level: 1,
+            level_started_at: now,
+            n_entrants,
+            eliminated: Vec::new(),
+            results: Vec::new(),
+        }
+    }
+
+    /// Called once per handout, before forced bets are posted. Returns whether blinds went up.
+    pub fn advance(&mut self, tournament: &TournamentConfig, now: Duration) -> bool {
+        self.hands_in_level += 1;
+        let is_level_over = match tournament.level_duration {
+            LevelDuration::Hands(n_hands) => self.hands_in_level > n_hands,
+            LevelDuration::Time(duration) => now - self.level_started_at >= duration,
+        };
+        if is_level_over && self.level + 1 < tournament.levels.len() {
+            self.level += 1;
+            self.hands_in_level = 1;
+            self.level_started_at = now;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Place of the next player to bust
+    pub fn next_place(&self) -> u8 {
+        self.n_entrants - self.eliminated.len() as u8
+    }
+
+    pub fn eliminate(&mut self, seat_index: u8, name: &str) {
+        self.eliminated.push((seat_index, name.to_string()));
+    }
+
+    pub fn finish(&mut self, tournament: &TournamentConfig, winner_seat_index: u8, winner: &str) {
+        let mut results = vec![TournamentResult {
+            seat_index: winner_seat_index,
+            name: winner.to_string(),
+            place: 1,
+            payout: tournament.payout(1, self.n_entrants),
+        }];
+        for (i, (seat_index, name)) in self.eliminated.iter().rev().enumerate() {
+            let place = i as u8 + 2;
+            results.push(TournamentResult {
+                seat_index: *seat_index,
+                name: name.clone(),
+                place,
+                payout: tournament.payout(place, self.n_entrants),
+            });
+        }
+        self.results = results;
+    }
+}
@@ -1,331 +1,225 @@
-use bevy::{prelude::*, time::Stopwatch, utils::hashbrown::HashMap};
-use rand::seq::SliceRandom;
+use bevy::{ecs::system::EntityCommands, prelude::*, time::Stopwatch};
 
 use super::{
-    betting::{Betting, PlayerAttributes},
-    compare_hands::compare_hands,
+    actions::Action,
     components::{
-        Amount, Bankroll, BoardCards, Card, HoleCards, HoleCardsFaceUp, HoleCardsHidden,
-        MovedChips, Player, PlayerInAction, PlayerIsActive, PlayerIsHero, PooledPot, Pot,
-        PreflopPosition, SeatIndex, Stack, Street,
+        Bankroll, BoardCards, Card, HoleCards, HoleCardsFaceUp, HoleCardsHidden, MovedChips,
+        Player, PlayerInAction, PlayerIsActive, PlayerIsAllIn, PlayerIsEliminated, PlayerIsHero,
+        PooledPot, Pot, PreflopPosition, SeatIndex, ShownHand, Stack, Street,
     },
-    deck::Deck,
+    engine::{GameEvent, GameState, Phase},
     events::{HeroMoved, TableUpdated},
-    resources::DeckResource,
+    made_hand::best_hand,
+    resources::{GameStateResource, SeatStrategies, TableConfig},
     states::HandoutState,
 };
 
-pub fn start_new_handout(
-    mut commands: Commands,
-    mut next_state: ResMut<NextState<HandoutState>>,
-    mut q_players: Query<
-        (
-            Entity,
-            &SeatIndex,
-            &mut HoleCards,
-            &mut MovedChips,
-            &mut Stack,
-            &mut Bankroll,
-            &mut PreflopPosition,
-        ),
-        With<Player>,
-    >,
-    mut q_board: Query<(&mut BoardCards, &mut Pot, &mut PooledPot, &mut Street)>,
-    mut deck_resource: ResMut<DeckResource>,
-    mut timer: Local<Stopwatch>,
-    time: Res<Time>,
-) {
-    timer.tick(time.delta());
-    if timer.elapsed().as_millis() < 1000 {
-        return;
-    }
-    timer.reset();
-    deck_resource.deck = Deck::new_shuffled();
-    for (
-        player_id,
-        seat_index,
-        mut hole_cards,
-        mut moved_chips,
-        mut stack,
-        mut bankroll,
-        mut preflop_position,
-    ) in q_players.iter_mut()
-    {
-        // moved_chips here may contain winnings
-        *stack.amount_mut() += moved_chips.amount();
-        *moved_chips.amount_mut() = 0;
-
-        if stack.amount() < 2 {
-            let top_up_amount = (200 - stack.amount()).min(bankroll.amount());
-            *stack.amount_mut() += top_up_amount;
-            *bankroll.amount_mut() -= top_up_amount;
-        }
-
-        preflop_position.0 = (preflop_position.0 + 1) % 6;
-        if preflop_position.0 == 0 {
-            // big blind
-            *stack.amount_mut() -= 2;
-            *moved_chips.amount_mut() += 2;
-        }
-        if preflop_position.0 == 1 {
-            // small blind
-            *stack.amount_mut() -= 1;
-            *moved_chips.amount_mut() += 1;
-        }
-        commands.entity(player_id).insert(PlayerIsActive);
-        if preflop_position.0 == 5 {
-            commands.entity(player_id).insert(PlayerInAction);
-        }
-        if seat_index.0 != 3 {
-            commands.entity(player_id).remove::<HoleCardsFaceUp>();
-        }
-        hole_cards.0[0] = deck_resource.deck.draw();
-        hole_cards.0[1] = deck_resource.deck.draw();
-    }
-    let (mut board_cards, mut pot, mut pooled_pot, mut street) = q_board.single_mut();
-    for i in 0..5 {
-        board_cards.0[i] = Card::default();
-    }
-    *pot.amount_mut() = 3;
-    *pooled_pot.amount_mut() = 0;
-    street.0 = 0;
-    next_state.set(HandoutState::ExpectingMove);
-}
-
+/// The hero acts through the move controls, bots after a thinking delay
 pub fn make_move(
-    mut commands: Commands,
-    mut next_state: ResMut<NextState<HandoutState>>,
-    mut players_queries: ParamSet<(
-        Query<(Entity, &mut Stack, &mut MovedChips), With<PlayerInAction>>,
-        Query<PlayerAttributes, With<Player>>,
-    )>,
-    mut q_pot: Query<&mut Pot>,
-    mut table_updated_event_writer: EventWriter<TableUpdated>,
+    mut game: ResMut<GameStateResource>,
+    mut strategies: ResMut<SeatStrategies>,
     mut hero_moved_event_reader: EventReader<HeroMoved>,
     mut timer: Local<Stopwatch>,
-    q_street: Query<&Street>,
-    q_hero: Query<Option<&PlayerIsHero>, With<PlayerInAction>>,
     time: Res<Time>,
 ) {
-    let player_is_hero = q_hero.single().is_some();
-
-    let move_amount = if player_is_hero {
-        if let Some(hero_moved_event) = hero_moved_event_reader.read().next() {
-            (hero_moved_event.0).0
-        } else {
+    let Some(actor_index) = game.state.in_action else {
+        return;
+    };
+    let is_hero = actor_index == game.state.config.hero_seat() as usize;
+    let action = if is_hero {
+        let Some(hero_moved_event) = hero_moved_event_reader.read().next() else {
             return;
-        }
+        };
+        hero_moved_event.0
     } else {
         timer.tick(time.delta());
         if timer.elapsed().as_millis() < 1000 {
             return;
         }
         timer.reset();
-        let street = q_street.single().0;
-        let q_players = players_queries.p1();
-        let players = q_players.iter().collect::<Vec<_>>();
-        let betting = Betting::new(&players, q_pot.single().amount(), street);
-        let options: Vec<i32> = betting.move_options();
-        let mut rng = rand::thread_rng();
-        let weights = if street == 0 {
-            vec![0.7, 0.2, 0.1]
-        } else {
-            if options.len() == 2 {
-                vec![0.5, 0.5]
-            } else {
-                vec![0.6, 0.3, 0.1]
-            }
-        };
-        let weights_map = options
-            .iter()
-            .zip(weights.iter())
-            .map(|(i, w)| (*i, *w))
-            .collect::<HashMap<i32, f32>>();
-        let move_amount: i32 = *options
-            .choose_weighted(&mut rng, |i| weights_map[i])
-            .unwrap();
-        move_amount
+        game.state
+            .with_rng(|state, rng| strategies.decide(state, rng))
     };
-
-    let q_actor = players_queries.p0();
-    let (actor_id, _, _) = q_actor.single();
-    if move_amount < 0 {
-        commands.entity(actor_id).remove::<PlayerIsActive>();
-        commands.entity(actor_id).insert(HoleCardsHidden);
-    } else {
-        let mut pot = q_pot.single_mut();
-        let mut q_actor = players_queries.p0();
-        let (_, mut stack, mut moved_chips) = q_actor.single_mut();
-        let positive_move_amount = move_amount as u32;
-        *moved_chips.amount_mut() += positive_move_amount;
-        if positive_move_amount <= stack.amount() {
-            *stack.amount_mut() -= positive_move_amount;
-        } else {
-            *stack.amount_mut() = 0;
+    let events = match game.state.apply_action(action) {
+        Ok(events) => events,
+        Err(error) => {
+            warn!(
+                "Illegal action {} by {}: {}",
+                action, game.state.seats[actor_index].name, error
+            );
+            if is_hero {
+                // The hero picks another move
+                return;
+            }
+            // Rather than asking the bot again on every tick
+            let legal_actions = game.state.legal_actions().unwrap();
+            let fallback = if legal_actions.can_check() {
+                Action::Check
+            } else {
+                Action::Fold
+            };
+            game.state
+                .apply_action(fallback)
+                .expect("Checking or folding is always legal")
         }
-        *pot.amount_mut() += positive_move_amount;
-    }
-    commands.entity(actor_id).remove::<PlayerInAction>();
-
-    let q_players = players_queries.p1();
-    let players = q_players.iter().collect::<Vec<_>>();
+    };
+    log_events(&game.state, &events);
+}
 
-    // Chip amounts are changed, but the fact that actor folded is not yet reflected in q_players
-    let mut betting = Betting::new(&players, q_pot.single().amount(), q_street.single().0);
-    if move_amount < 0 {
-        betting.set_actor_inactive();
+/// Steps through the phases that need no decision, slowly enough for each one to be seen
+pub fn advance_handout(
+    mut game: ResMut<GameStateResource>,
+    mut timer: Local<Stopwatch>,
+    time: Res<Time>,
+) {
+    let state = &game.state;
+    let delay_ms = match state.phase {
+        Phase::Betting | Phase::TournamentFinished => return,
+        // Running the board out: let every street be seen
+        Phase::Dealing if !state.betting().is_betting_possible() => 1000,
+        Phase::Settling if state.seats.iter().any(|seat| seat.shows_cards) => 1500,
+        Phase::Settling => 500,
+        Phase::HandoutEnded => 1000,
+        _ => 0,
+    };
+    timer.tick(time.delta());
+    if timer.elapsed().as_millis() < delay_ms {
+        return;
     }
-    let next_actor_id = betting.next_actor_id();
+    timer.reset();
+    game.state.clock = time.elapsed();
+    let events = game.state.advance();
+    log_events(&game.state, &events);
+}
 
-    match next_actor_id {
-        Some(next_actor_id) => {
-            commands.entity(next_actor_id).insert(PlayerInAction);
-            next_state.set(HandoutState::ExpectingMove);
-        }
-        None => {
-            next_state.set(HandoutState::ExpectingPool);
+fn log_events(state: &GameState, events: &[GameEvent]) {
+    for event in events {
+        match event {
+            GameEvent::PlayerEliminated { seat_index, place } => {
+                println!("{} finishes #{}", state.seats[*seat_index].name, place);
+            }
+            GameEvent::TournamentFinished { winner_seat_index } => {
+                println!(
+                    "{} wins the tournament",
+                    state.seats[*winner_seat_index].name
+                );
+            }
+            GameEvent::HandoutStarted { seed } => {
+                println!("Handout seed: {}", seed);
+            }
+            GameEvent::CardsShown { seat_index } => {
+                let seat = &state.seats[*seat_index];
+                let board = dealt_board(state);
+                if let Some(made_hand) = best_hand(&seat.hole_cards, &board, state.config.variant) {
+                    println!("{} shows {}", seat.name, made_hand);
+                }
+            }
+            GameEvent::BlindsUp(level) => {
+                println!(
+                    "Blinds up: {}/{} ante {}",
+                    level.small_blind.0, level.big_blind.0, level.ante.0
+                );
+            }
+            _ => {}
         }
     }
-    table_updated_event_writer.send(TableUpdated);
 }
 
-pub fn pool_moved_chips(
-    mut next_state: ResMut<NextState<HandoutState>>,
-    mut q_players: Query<(Entity, &mut MovedChips, Option<&PlayerIsActive>), With<Player>>,
-    mut q_pot: Query<&mut PooledPot>,
-    mut table_updated_event_writer: EventWriter<TableUpdated>,
-    q_street: Query<&Street>,
-) {
-    let street: u8 = q_street.single().0;
-    let mut n_active = 0;
-    for (_player_id, mut moved_chips, is_active) in q_players.iter_mut() {
-        let mut pooled_pot = q_pot.single_mut();
-        *pooled_pot.amount_mut() += moved_chips.amount();
-        *moved_chips.amount_mut() = 0;
-        table_updated_event_writer.send(TableUpdated);
-        if is_active.is_some() {
-            n_active += 1;
-        }
-    }
-    if n_active == 1 {
-        next_state.set(HandoutState::ExpectingWinningsAttribution);
-    } else if street == 3 {
-        next_state.set(HandoutState::ExpectingShowdown);
-    } else {
-        next_state.set(HandoutState::ExpectingDeal);
-    }
+/// Community cards dealt so far
+fn dealt_board(state: &GameState) -> Vec<Card> {
+    state
+        .board
+        .iter()
+        .filter(|card| card.is_defined())
+        .cloned()
+        .collect()
 }
 
-pub fn deal_community_cards(
+/// Mirrors the engine state into the components read by the graphics
+pub fn sync_table(
     mut commands: Commands,
     mut next_state: ResMut<NextState<HandoutState>>,
-    mut deck_resource: ResMut<DeckResource>,
     mut table_updated_event_writer: EventWriter<TableUpdated>,
-    mut q_board: Query<&mut BoardCards>,
-    mut q_street: Query<&mut Street>,
-    q_players: Query<PlayerAttributes, With<Player>>,
-    q_pot: Query<&Pot>,
+    mut config: ResMut<TableConfig>,
+    mut q_players: Query<
+        (
+            Entity,
+            &SeatIndex,
+            &mut Bankroll,
+            &mut Stack,
+            &mut MovedChips,
+            &mut HoleCards,
+            &mut PreflopPosition,
+            Option<&PlayerIsHero>,
+        ),
+        With<Player>,
+    >,
+    mut q_board: Query<(&mut BoardCards, &mut Pot, &mut PooledPot, &mut Street)>,
+    game: Res<GameStateResource>,
 ) {
-    let mut board_cards: Mut<BoardCards> = q_board.single_mut();
-    let cards: &mut [Card; 5] = &mut board_cards.0;
-    let mut street: Mut<Street> = q_street.single_mut();
-    if !cards[0].is_defined() {
-        // Preflop
-        cards[0] = deck_resource.deck.draw();
-        cards[1] = deck_resource.deck.draw();
-        cards[2] = deck_resource.deck.draw();
-        street.0 = 1;
-    } else if !cards[3].is_defined() {
-        // Flop
-        cards[3] = deck_resource.deck.draw();
-        street.0 = 2;
-    } else if !cards[4].is_defined() {
-        // Turn
-        cards[4] = deck_resource.deck.draw();
-        street.0 = 3;
-    } else {
-        // River
-        unreachable!();
-    }
-    table_updated_event_writer.send(TableUpdated);
+    let state = &game.state;
+    // Stakes follow the tournament blind levels
+    *config = state.config.clone();
 
-    let pot = q_pot.single();
-    let players = q_players.iter().collect::<Vec<_>>();
-    let betting = Betting::new(&players, pot.amount(), q_street.single().0);
+    let board = dealt_board(state);
+    for (
+        player_id,
+        seat_index,
+        mut bankroll,
+        mut stack,
+        mut moved_chips,
+        mut hole_cards,
+        mut preflop_position,
+        is_hero,
+    ) in q_players.iter_mut()
+    {
+        let seat_index = seat_index.0 as usize;
+        let seat = &state.seats[seat_index];
+        bankroll.0 = seat.bankroll;
+        stack.0 = seat.stack;
+        moved_chips.0 = seat.front;
+        hole_cards.0.clone_from(&seat.hole_cards);
+        preflop_position.0 = seat.preflop_position;
+
+        let mut player = commands.entity(player_id);
+        toggle_marker(&mut player, PlayerIsActive, seat.is_active);
+        toggle_marker(
+            &mut player,
+            PlayerInAction,
+            state.in_action == Some(seat_index),
+        );
+        toggle_marker(&mut player, PlayerIsAllIn, seat.is_all_in);
+        toggle_marker(&mut player, PlayerIsEliminated, seat.is_eliminated);
+        toggle_marker(&mut player, HoleCardsHidden, !seat.is_active);
+        toggle_marker(
+            &mut player,
+            HoleCardsFaceUp,
+            is_hero.is_some() || seat.shows_cards,
+        );
+        let shown_hand = seat
+            .shows_cards
+            .then(|| best_hand(&seat.hole_cards, &board, state.config.variant))
+            .flatten();
+        match shown_hand {
+            Some(made_hand) => player.insert(ShownHand(made_hand.description())),
+            None => player.remove::<ShownHand>(),
+        };
+    }
 
-    let next_actor_id = betting.next_actor_id().unwrap(); // guaranteed to exist
-    commands.entity(next_actor_id).insert(PlayerInAction);
-    next_state.set(HandoutState::ExpectingMove);
-}
+    let (mut board_cards, mut pot, mut pooled_pot, mut street) = q_board.single_mut();
+    board_cards.0.clone_from(&state.board);
+    pot.0 = state.pot;
+    pooled_pot.0 = state.pooled_pot;
+    street.0 = state.street;
 
-pub fn do_showdown(
-    mut commands: Commands,
-    mut next_state: ResMut<NextState<HandoutState>>,
-    q_opponents: Query<Entity, (With<PlayerIsActive>, Without<PlayerIsHero>)>,
-) {
-    for opponent_id in q_opponents.iter() {
-        commands.entity(opponent_id).insert(HoleCardsFaceUp);
-    }
-    next_state.set(HandoutState::ShowdownMade);
+    next_state.set(HandoutState::from(state.phase));
+    table_updated_event_writer.send(TableUpdated);
 }
 
-pub fn attribute_winnings(
-    mut next_state: ResMut<NextState<HandoutState>>,
-    mut table_updated_event_writer: EventWriter<TableUpdated>,
-    mut q_players: Query<(Entity, &HoleCards, &mut MovedChips), With<PlayerIsActive>>,
-    mut q_pot: Query<&mut Pot>,
-    mut timer: Local<Stopwatch>,
-    q_board: Query<&BoardCards>,
-    time: Res<Time>,
-) {
-    timer.tick(time.delta());
-    if timer.elapsed().as_millis() < 500 {
-        return;
-    }
-    timer.reset();
-    let players = q_players.iter().collect::<Vec<_>>();
-    if players.len() == 1 {
-        for (_player_id, _, mut moved_chips) in q_players.iter_mut() {
-            *moved_chips.amount_mut() += q_pot.single().amount();
-            *q_pot.single_mut().amount_mut() = 0;
-            table_updated_event_writer.send(TableUpdated);
-        }
+fn toggle_marker<T: Component>(entity: &mut EntityCommands, marker: T, is_present: bool) {
+    if is_present {
+        entity.insert(marker);
     } else {
-        let board_cards: &BoardCards = q_board.single();
-        let mut full_hands: Vec<Vec<Card>> = (0..players.len())
-            .map(|_| board_cards.0.to_vec().clone())
-            .collect();
-        for (i, (_, hole_cards, _)) in players.iter().enumerate() {
-            full_hands[i].extend(hole_cards.0.iter().map(|x| (*x).clone()));
-        }
-        let winning_hand_indices = compare_hands(&full_hands);
-        let win_amount: u32 = if winning_hand_indices.len() > 0 {
-            q_pot.single().amount() / winning_hand_indices.len() as u32
-        } else {
-            0
-        };
-        for winning_hand_index in winning_hand_indices {
-            for (i, (_player_id, _, mut moved_chips)) in q_players.iter_mut().enumerate() {
-                if i == winning_hand_index {
-                    *moved_chips.amount_mut() += win_amount;
-                }
-            }
-        }
-        *q_pot.single_mut().amount_mut() = 0;
-        table_updated_event_writer.send(TableUpdated);
-    }
-    next_state.set(HandoutState::HandoutEnded);
-}
-
-pub fn on_showdown_made(
-    mut next_state: ResMut<NextState<HandoutState>>,
-    mut timer: Local<Stopwatch>,
-    time: Res<Time>,
-) {
-    timer.tick(time.delta());
-    if timer.elapsed().as_millis() < 1000 {
-        return;
+        entity.remove::<T>();
     }
-    timer.reset();
-    next_state.set(HandoutState::ExpectingWinningsAttribution);
 }